@@ -1,15 +1,17 @@
-use std::collections::{HashSet};
-use crate::{Cache, DNMT, M, N};
+use crate::cache::Cache;
+use crate::tedges::TEdge;
 use crate::tgraph::{TGraph};
 use rand::{thread_rng};
 use rand::seq::SliceRandom;
-use crate::helper::{all_ones};
+use crate::bits::{Bits, Dim, EdgeBits, Width};
+use crate::helper::{all_ones, each_ones};
 
-pub fn select(g: &TGraph, cache: &Cache) -> bool{
+/// Whether the subtree of a graph may contain a clique without optimal spanner.
+pub fn select<const N: usize>(g: &TGraph<N>, cache: &Cache<N>) -> bool where Dim<N>: Width {
     ! (g.is_dismountable() || g.has_pivot_vertex(cache) || g.has_optimal_spanner(2))
 }
 
-impl TGraph {
+impl<const N: usize> TGraph<N> where Dim<N>: Width {
     // pub fn get_components(&self) -> Vec<u8>{
     //     // faster than union-find
     //     let mut comps: [u8; N] = [0; N];
@@ -31,63 +33,61 @@ impl TGraph {
     //     return comps.into_iter().unique().collect();
     // }
 
+    /// Whether every pair of vertices has an edge.
     pub fn is_clique(&self) -> bool {
-        self.nb_edges == (M as u8)
+        self.nb_edges == (Self::M as u8)
     }
 
 
 
     /////////////////////////////////////////////// DISMOUNTING
 
+    /// Whether some vertex is (or will be, in every clique extending this graph) the other end
+    /// of the first edge of a vertex and of the last edge of another vertex.
     pub fn is_dismountable(&self) -> bool {
         let mins = self.dismountability.mins;
         let maxs = self.dismountability.maxs;
-        if mins & maxs != 0 {
+        if mins & maxs != Bits::ZERO {
             return true;
         }
 
         let missing_ng = self.dismountability.missing_ng;
-        for u in 0..N{
-            if missing_ng[u]!= 0 && missing_ng[u] & mins == missing_ng[u]{
-                return true;
-            }
-        }
-        false
+        missing_ng.iter().any(|&ng| ng != Bits::ZERO && ng & mins == ng)
     }
 
 
 
     /////////////////////////////////////////////// PIVOTING
 
+    /// Whether all vertices can reach each other (temporal connectivity).
     pub fn is_tc(&self) -> bool {
-        self.predecessors().iter().all(|p| *p == all_ones())
+        self.predecessors().iter().all(|p| *p == all_ones::<N>())
     }
 
-    fn is_tc_without(&self, removed: u32, cand_remove: usize) -> bool {
-        let mut preds = [0; N];
-        for i in 0..self.n as usize {
-            preds[i] |= 1 << i;
-        }
+    fn is_tc_without(&self, removed: EdgeBits<N>, cand_remove: usize) -> bool {
+        let mut preds = each_ones::<N>();
         for (i, e) in self.tedges().iter().enumerate() {
-            if i != cand_remove && removed & 1 << i == 0 {
+            if i != cand_remove && !removed.has(i) {
                 preds[e.0 as usize] |= preds[e.1 as usize];
                 preds[e.1 as usize] = preds[e.0 as usize];
             }
         }
-        preds.iter().all(|p| *p == all_ones())
+        preds.iter().all(|p| *p == all_ones::<N>())
     }
 
+    /// Whether some vertex has no edge.
     pub fn has_isolated_vertex(&self) -> bool {
         self.dismountability.degrees.into_iter().any(|deg| deg == 0)
     }
 
-    pub fn has_pivot_vertex(&self, cache: &Cache) -> bool {
-        let mut fpreds2 = self.reachability.preds2.clone();
+    /// Whether the cliques extending this graph have a pivot vertex.
+    pub fn has_pivot_vertex(&self, cache: &Cache<N>) -> bool {
+        let mut fpreds2 = self.reachability.preds2;
 
         // For each non-edge, the two vertices will eventually merge their full predecessors
         // (eventually = because clique)
-        for i in 0..M {
-            if self.edges_bits & 1 << i == 0{
+        for i in 0..Self::M {
+            if !self.edges_bits.has(i) {
                 let te = cache.edges[i];
                 let u = te.0 as usize;
                 let v = te.1 as usize;
@@ -96,82 +96,90 @@ impl TGraph {
             }
         }
 
-        let inter = fpreds2.iter().fold(all_ones(), |res, val| res & *val);
-        inter != 0
+        let inter = fpreds2.iter().fold(all_ones::<N>(), |res, val| res & *val);
+        inter != Bits::ZERO
     }
 
+    /// A vertex that is reached by all vertices at some time, and then reaches all vertices.
+    pub fn pivot_vertex(&self) -> Option<u8> {
+        let inter = self.reachability.preds2.iter().fold(all_ones::<N>(), |res, val| res & *val);
+        if inter == Bits::ZERO {
+            None
+        } else {
+            Some(inter.trailing_zeros() as u8)
+        }
+    }
+
+    /// Size of a spanner obtained by removing edges greedily (down to 2n-3 edges at best).
     pub fn greedy_spanner_size(&self) -> u8 {
-        let mut removed = 0_u32;
+        self.tedges().len() as u8 - self.greedy_removed().count_ones() as u8
+    }
+
+    /// Spanner obtained by removing edges greedily, see [`TGraph::greedy_spanner_size`]
+    /// (only meaningful if the graph is temporally connected).
+    pub fn greedy_spanner(&self) -> Vec<TEdge> {
+        let removed = self.greedy_removed();
+        self.tedges().iter().enumerate()
+            .filter(|(i, _)| !removed.has(*i))
+            .map(|(_, e)| *e)
+            .collect()
+    }
+
+    // positions in tedges() of the edges removed by the greedy spanner
+    fn greedy_removed(&self) -> EdgeBits<N> {
+        let mut removed = EdgeBits::<N>::ZERO;
         let mut nb_removed = 0;
-        let target_removed = self.tedges().len() as u8 - DNMT;
+        let target_removed = (self.tedges().len() as u8).saturating_sub(Self::DNMT);
         for i in 0..self.nb_edges {
             if self.is_tc_without(removed, i as usize) {
-                removed |= 1 << i;
+                removed |= EdgeBits::<N>::bit(i as usize);
                 nb_removed += 1;
                 if nb_removed == target_removed{
                     break;
                 }
             }
         }
-        return self.tedges().len() as u8 - nb_removed;
+        removed
     }
 
+    /// Same as [`TGraph::greedy_spanner_size`], trying the edges in random order.
     pub fn random_spanner_size(&self) -> u8 {
         let mut rand_indices: Vec<usize> = (0..self.nb_edges as usize).collect();
         rand_indices.shuffle(&mut thread_rng());
-        let mut removed = 0_u32;
+        let mut removed = EdgeBits::<N>::ZERO;
         let mut nb_removed = 0;
-        let target_removed = self.tedges().len() as u8 - DNMT;
+        let target_removed = (self.tedges().len() as u8).saturating_sub(Self::DNMT);
         // for i in (0..self.nb_edges).map(|_| thread_rng().gen_range(0..self.nb_edges)) {
         for i in rand_indices {
-            if self.is_tc_without(removed, i as usize) {
-                removed |= 1 << i;
+            if self.is_tc_without(removed, i) {
+                removed |= EdgeBits::<N>::bit(i);
                 nb_removed += 1;
                 if nb_removed == target_removed{
                     break;
                 }
             }
         }
-        return self.tedges().len() as u8 - nb_removed;
+        self.tedges().len() as u8 - nb_removed
     }
 
+    /// Whether a spanner of size at most 2n-3 is found within `nb_try` attempts.
     // Here optimal means 2n-3 or 2n-4 edges
     pub fn has_optimal_spanner(&self, nb_try: u32) -> bool {
-        if self.nb_edges < (2 * self.n - 4) || !self.is_tc() {
+        if (self.nb_edges as usize) < (2 * N).saturating_sub(4) || !self.is_tc() {
             return false;
         }
 
-        if self.greedy_spanner_size() <= DNMT{
+        if self.greedy_spanner_size() <= Self::DNMT{
             return true;
         }
 
         let mut i = 1;
         while i < nb_try {
-            if self.random_spanner_size() <= DNMT {
+            if self.random_spanner_size() <= Self::DNMT {
                 return true;
             }
             i += 1;
         }
-        return false;
-    }
-}
-
-/////////// HELPER ////////////////////////////
-
-pub fn range_set_vec() -> Vec<HashSet<u8>> {
-    let mut preds: Vec<HashSet<u8>> = Vec::with_capacity(N);
-    for i in 0..N as u8{
-        let mut set: HashSet<u8> = HashSet::with_capacity(N);
-        set.insert(i);
-        preds.push(set);
-    }
-    preds
-}
-pub fn empty_set_vec() -> Vec<HashSet<u8>> {
-    let mut preds: Vec<HashSet<u8>> = Vec::with_capacity(N);
-    for _ in 0..N as u8{
-        preds.push(HashSet::with_capacity(N));
+        false
     }
-    preds
 }
-
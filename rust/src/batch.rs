@@ -0,0 +1,143 @@
+use std::time::Instant;
+use serde::Serialize;
+use crate::bits::{Dim, Width};
+use crate::cache::Cache;
+use crate::parallel::{get_pool, visit_par_cut};
+use crate::tedges::TEdge;
+use crate::tgraph::TGraph;
+use crate::visitor::{Control, Visitor};
+
+// Batches are meant to be run by separate processes (e.g. one job per node of a cluster), like
+// those of TGraphBatchIterator in the C++ version. They only depend on N and on the cut, so that
+// every process computes the same list of batches, and the results of the batches add up.
+
+/// Split of the generation tree into batches: the subtrees of the graphs with at least `cut`
+/// edges, in a deterministic order. The graphs above them are part of batch 0 (which has no
+/// subtree if no graph has `cut` edges).
+pub struct Batches<'a, const N: usize> where Dim<N>: Width {
+    cache: &'a Cache<N>,
+    roots: Vec<TGraph<N>>,
+    top: Vec<TGraph<N>>,
+}
+
+/// Result of a batch, see [`Batches::summarize`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BatchSummary {
+    pub batch: usize,
+    /// Root of the subtree of the batch (empty if there is no subtree, see [`Batches`]).
+    pub root: Vec<TEdge>,
+    /// Numbers of graphs, temporally connected graphs and cliques in the batch.
+    pub count: u128,
+    pub tc: u128,
+    pub cliques: u128,
+    /// Number of labelled graphs represented by the graphs of the batch.
+    pub labelled: u128,
+    /// Number of symmetric graphs in the batch.
+    pub symmetric: u128,
+    pub seconds: f64,
+}
+
+impl<'a, const N: usize> Batches<'a, N> where Dim<N>: Width {
+    /// Batches rooted at the graphs with at least N edges (as in the C++ version).
+    pub fn new(cache: &'a Cache<N>) -> Batches<'a, N> {
+        Self::with_cut(N as u8, cache)
+    }
+
+    /// Batches rooted at the graphs with at least `cut` edges (a cut of 0 is taken as 1).
+    pub fn with_cut(cut: u8, cache: &'a Cache<N>) -> Batches<'a, N> {
+        let (roots, top) = get_pool(TGraph::new(), None, cut.max(1) - 1, cache);
+        Batches { cache, roots, top }
+    }
+
+    pub fn number_of_batches(&self) -> usize {
+        self.roots.len().max(1)
+    }
+
+    /// Root of the subtree of batch `k`, if any.
+    pub fn root(&self, k: usize) -> Option<&TGraph<N>> {
+        self.roots.get(k)
+    }
+
+    /// Visits the graphs of batch `k` (the subtree is itself explored in parallel, see
+    /// [`crate::parallel::visit_par`]). For batch 0, the graphs above the batches are visited
+    /// first, whatever the visitor returns for them. Returns false if the visitor stopped.
+    pub fn execute<V: Visitor<N> + Send + Sync>(&self, k: usize, visitor: &mut V) -> bool {
+        if k == 0 {
+            for g in &self.top {
+                if visitor.visit(g) == Control::Stop {
+                    return false;
+                }
+            }
+        }
+        match self.roots.get(k) {
+            Some(root) => visit_par_cut(root.clone(), visitor, root.nb_edges + 4, self.cache),
+            None => true,
+        }
+    }
+
+    /// Counts the graphs of batch `k`, see [`Batches::execute`].
+    pub fn summarize(&self, k: usize) -> BatchSummary {
+        let start = Instant::now();
+        let mut tally = Tally::default();
+        self.execute(k, &mut tally);
+        BatchSummary {
+            batch: k,
+            root: self.root(k).map_or(vec![], |g| g.tedges().to_vec()),
+            count: tally.counts[0],
+            tc: tally.counts[1],
+            cliques: tally.counts[2],
+            labelled: tally.counts[3],
+            symmetric: tally.counts[4],
+            seconds: start.elapsed().as_secs_f64(),
+        }
+    }
+}
+
+// Graphs, temporally connected graphs, cliques, labelled graphs and symmetric graphs
+#[derive(Default)]
+struct Tally {
+    counts: [u128; 5],
+}
+
+impl<const N: usize> Visitor<N> for Tally where Dim<N>: Width {
+    fn visit(&mut self, g: &TGraph<N>) -> Control {
+        let factorial: u128 = (1..=N as u128).product();
+        let order = g.automorphism_group().order();
+        let counts = [1, g.is_tc() as u128, g.is_clique() as u128, factorial / order as u128, (order > 1) as u128];
+        for (total, nb) in self.counts.iter_mut().zip(counts) {
+            *total += nb;
+        }
+        Control::Descend
+    }
+
+    fn fork(&self) -> Self {
+        Tally::default()
+    }
+
+    fn merge(&mut self, other: Self) {
+        for (total, nb) in self.counts.iter_mut().zip(other.counts) {
+            *total += nb;
+        }
+    }
+}
+
+#[test]
+pub fn batches_add_up() {
+    let cache = crate::cache::compute_cache::<5>();
+    for (batches, cut) in [(Batches::new(&cache), 5), (Batches::with_cut(2, &cache), 2)] {
+        assert!(batches.number_of_batches() > 1);
+        // as in the C++ version, the graphs with fewer edges than the cut are above the batches
+        assert!(batches.roots.iter().all(|g| g.nb_edges() >= cut) && batches.top.iter().all(|g| g.nb_edges() < cut));
+        let summaries: Vec<BatchSummary> = (0..batches.number_of_batches()).map(|k| batches.summarize(k)).collect();
+        let total = |f: fn(&BatchSummary) -> u128| summaries.iter().map(f).sum::<u128>();
+        assert_eq!(total(|s| s.count), 15378);
+        assert_eq!(total(|s| s.tc), 10207);
+        assert_eq!(total(|s| s.cliques), 4524);
+        assert_eq!(total(|s| s.labelled), 1844066);
+        assert_eq!(total(|s| s.symmetric), 18);
+    }
+    let cache = crate::cache::compute_cache::<3>();
+    let batches = Batches::new(&cache);
+    assert_eq!(batches.number_of_batches(), 1);
+    assert_eq!(batches.summarize(0).count, 4);
+}
@@ -0,0 +1,80 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+// Unsigned integers used as bitsets (over the vertices or over the edges of the graph)
+pub trait Bits: Copy + Eq + Ord + Hash + Debug + Default + Send + Sync + 'static
+    + BitAnd<Output=Self> + BitOr<Output=Self> + BitXor<Output=Self> + Not<Output=Self>
+    + BitAndAssign + BitOrAssign + BitXorAssign
+{
+    const ZERO: Self;
+    const WIDTH: usize;
+    fn bit(i: usize) -> Self;
+    fn low(nb: usize) -> Self; // the nb lowest bits
+    fn has(self, i: usize) -> bool;
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+}
+
+macro_rules! impl_bits {
+    ($($t:ty),*) => {$(
+        impl Bits for $t {
+            const ZERO: Self = 0;
+            const WIDTH: usize = <$t>::BITS as usize;
+            #[inline]
+            fn bit(i: usize) -> Self {
+                1 << i
+            }
+            #[inline]
+            fn low(nb: usize) -> Self {
+                if nb >= Self::WIDTH { !0 } else { (1 << nb) - 1 }
+            }
+            #[inline]
+            fn has(self, i: usize) -> bool {
+                self & 1 << i != 0
+            }
+            #[inline]
+            fn count_ones(self) -> u32 {
+                <$t>::count_ones(self)
+            }
+            #[inline]
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+        }
+    )*};
+}
+impl_bits!(u8, u16, u32, u64, u128);
+
+// Bitset widths for a given number of vertices: one bit per edge of K_N, one bit per vertex.
+pub struct Dim<const N: usize>;
+
+pub trait Width {
+    type EdgeBits: Bits;
+    type VertexBits: Bits;
+}
+
+macro_rules! impl_width {
+    ($($n:literal => ($e:ty, $v:ty)),*) => {$(
+        impl Width for Dim<$n> {
+            type EdgeBits = $e;
+            type VertexBits = $v;
+        }
+    )*};
+}
+impl_width!(1 => (u32, u8), 2 => (u32, u8), 3 => (u32, u8), 4 => (u32, u8),
+            5 => (u32, u8), 6 => (u32, u8), 7 => (u32, u8), 8 => (u32, u8),
+            9 => (u64, u16), 10 => (u64, u16), 11 => (u64, u16), 12 => (u128, u16));
+
+pub type EdgeBits<const N: usize> = <Dim<N> as Width>::EdgeBits;
+pub type VertexBits<const N: usize> = <Dim<N> as Width>::VertexBits;
+
+#[test]
+pub fn widths_fit() {
+    fn fits<const N: usize>() -> bool where Dim<N>: Width {
+        EdgeBits::<N>::WIDTH >= N * (N - 1) / 2 && VertexBits::<N>::WIDTH >= N
+    }
+    assert!(fits::<1>() && fits::<2>() && fits::<3>() && fits::<4>());
+    assert!(fits::<5>() && fits::<6>() && fits::<7>() && fits::<8>());
+    assert!(fits::<9>() && fits::<10>() && fits::<11>() && fits::<12>());
+}
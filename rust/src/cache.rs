@@ -1,70 +1,108 @@
-use crate::{M, N};
-use crate::helper::{are_adjacent, gen_edges, i2e};
+#[cfg(test)]
+use itertools::Itertools;
+use crate::bits::{Bits, Dim, EdgeBits, Width};
+use crate::helper::{are_adjacent, gen_edges, nb_pairs};
+#[cfg(test)]
+use crate::helper::i2e;
 use crate::tedges::TEdge;
 
-const CACHE_SIZE: usize = match N{4 => 9, 5 => 25, 6 => 75, 7 => 231, 8 => 763, _ => 75};
-
-pub struct Cache {
-    pub(crate) indicators: [u32; CACHE_SIZE],
-    pub(crate) delimiters: [usize; M+1],
-    pub(crate) edges: [TEdge; M],
+/// Precomputed data for a given number of vertices: the edges of K_N and all their matchings.
+pub struct Cache<const N: usize> where Dim<N>: Width {
+    pub(crate) indicators: Vec<EdgeBits<N>>,
+    pub(crate) delimiters: Vec<usize>,
+    pub(crate) edges: Vec<TEdge>,
     pub(crate) e2i: [[usize; N]; N],
-    pub(crate) adjacent_bits: [u32; M],
+    pub(crate) adjacent_bits: Vec<EdgeBits<N>>,
 }
 
 
 #[test]
 pub fn test() {
-    println!("{:?}", compute_cache().indicators);
+    println!("{:?}", compute_cache::<6>().indicators);
+}
+
+impl<const N: usize> Cache<N> where Dim<N>: Width {
+    /// The i-th edge of K_N (with time 0). Edges are ordered as (0,1), (0,2), ..., (1,2), ...
+    pub fn edge(&self, i: usize) -> TEdge {
+        self.edges[i]
+    }
+    /// Index of the edge between u and v.
+    pub fn edge_index(&self, u: u8, v: u8) -> usize {
+        self.e2i[u as usize][v as usize]
+    }
+    /// All nonempty matchings of K_N (as sets of edge indices), by increasing size.
+    pub fn matchings(&self) -> &[EdgeBits<N>] {
+        &self.indicators
+    }
+    /// Position of a matching in [`Cache::matchings`], if `bits` is a nonempty matching.
+    pub fn matching_index(&self, bits: EdgeBits<N>) -> Option<usize> {
+        let k = bits.count_ones() as usize;
+        if k == 0 || k >= self.delimiters.len() {
+            return None;
+        }
+        let first = self.delimiters[k - 1];
+        self.indicators[first..self.delimiters[k]].binary_search(&bits).ok().map(|i| first + i)
+    }
 }
 
-pub fn compute_cache() -> Cache {
-    let mut nb = 0;
-    let mut indicators: [u32; CACHE_SIZE] = [0 as u32; CACHE_SIZE];
-    let mut delimiters: [usize; M+1] = [CACHE_SIZE; M+1];
+/// Computes the cache for N vertices.
+pub fn compute_cache<const N: usize>() -> Cache<N> where Dim<N>: Width {
+    let m = nb_pairs(N);
+    let edges = gen_edges(N as u8);
+    let mut indicators: Vec<EdgeBits<N>> = vec![];
+    let mut delimiters: Vec<usize> = vec![0; m + 1];
 
-    let mut delim = 0;
-    for nb_new_edges in 0..(N/2)+1 {
-        for ind in 1..2_u32.pow(M as u32) {
-            if ind.count_ones() == nb_new_edges as u32 && is_independent(ind) {
-                indicators[nb] = ind;
-                nb += 1;
-            }
+    for (nb_new_edges, delim) in delimiters.iter_mut().enumerate() {
+        if nb_new_edges <= N/2 {
+            let first = indicators.len();
+            build_matchings::<N>(&edges, nb_new_edges, 0, EdgeBits::<N>::ZERO, 0, &mut indicators);
+            indicators[first..].sort(); // same order as a scan of all subsets
         }
-        delimiters[delim] = nb;
-        delim += 1;
+        *delim = indicators.len();
     }
     let mut e2i: [[usize; N]; N] = [[0; N]; N];
-    let mut i = 0;
-    for u in 0..N{
-        for v in (u+1)..N{
-            e2i[u][v] = i;
-            e2i[v][u] = i;
-            i += 1;
-        }
+    for (i, e) in edges.iter().enumerate(){
+        e2i[e.0 as usize][e.1 as usize] = i;
+        e2i[e.1 as usize][e.0 as usize] = i;
     }
-    let edges = gen_edges(N as u8);
-    let mut adjacent_bits:[u32; M] = [0; M];
-    for i in 0..M{
-        let mut adj = 0;
-        for j in 0..M{
-            if are_adjacent(&edges[i], &edges[j]){
-                adj |= 1 << j;
+    let adjacent_bits: Vec<EdgeBits<N>> = edges.iter().map(|e| {
+        let mut adj = EdgeBits::<N>::ZERO;
+        for (j, f) in edges.iter().enumerate(){
+            if are_adjacent(e, f){
+                adj |= EdgeBits::<N>::bit(j);
             }
         }
-        adjacent_bits[i] = adj;
-    }
+        adj
+    }).collect();
     Cache{indicators, delimiters, edges, e2i, adjacent_bits}
 }
 
-pub fn get_indicators(nb_cand_edges: usize, cache: &Cache) -> &[u32] {
-    return &cache.indicators[0..cache.delimiters[nb_cand_edges]];
+pub fn get_indicators<const N: usize>(nb_cand_edges: usize, cache: &Cache<N>) -> &[EdgeBits<N>] where Dim<N>: Width {
+    &cache.indicators[0..cache.delimiters[nb_cand_edges]]
 }
 
-pub fn is_independent(edge_ind: u32) -> bool{
+// Appends all matchings made of nb_edges more edges (of index at least `from`) to `acc`,
+// whose matched vertices are `used`.
+fn build_matchings<const N: usize>(edges: &[TEdge], nb_edges: usize, from: usize, acc: EdgeBits<N>, used: u32, matchings: &mut Vec<EdgeBits<N>>) where Dim<N>: Width {
+    if nb_edges == 0 {
+        if acc != EdgeBits::<N>::ZERO {
+            matchings.push(acc);
+        }
+        return;
+    }
+    for (i, e) in edges.iter().enumerate().skip(from) {
+        let ends = 1 << e.0 | 1 << e.1;
+        if used & ends == 0 {
+            build_matchings::<N>(edges, nb_edges - 1, i + 1, acc | EdgeBits::<N>::bit(i), used | ends, matchings);
+        }
+    }
+}
+
+#[cfg(test)]
+fn is_independent<const N: usize, B: Bits>(edge_ind: B) -> bool{
     let mut nodes: [u8; N] = [0; N];
-    for i in 0..M{
-        if edge_ind & 1 << i != 0 {
+    for i in 0..nb_pairs(N){
+        if edge_ind.has(i) {
             let e = i2e(N as u8, i as u8);
             if nodes[e.0 as usize] == 1 || nodes[e.1 as usize] == 1 {
                 return false;
@@ -82,4 +120,20 @@ pub fn clementine(){
     for i in 0..10{
         println!("{}", i);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn cache_sizes(){
+    // number of nonempty matchings in K_n (telephone numbers minus one)
+    assert_eq!(compute_cache::<1>().indicators.len(), 0);
+    assert_eq!(compute_cache::<2>().indicators.len(), 1);
+    assert_eq!(compute_cache::<4>().indicators.len(), 9);
+    assert_eq!(compute_cache::<6>().indicators.len(), 75);
+    assert_eq!(compute_cache::<8>().indicators.len(), 763);
+    assert_eq!(compute_cache::<12>().indicators.len(), 140151);
+    let cache = compute_cache::<5>();
+    let scanned: Vec<u32> = (1..1 << 10).filter(|&ind: &u32| is_independent::<5, u32>(ind))
+        .sorted_by_key(|ind| ind.count_ones()).collect();
+    assert_eq!(cache.indicators, scanned);
+    assert_eq!(cache.delimiters, [0, 10, 25, 25, 25, 25, 25, 25, 25, 25, 25]);
+}
@@ -0,0 +1,121 @@
+use crate::bits::{Dim, Width};
+use crate::cache::Cache;
+use crate::helper::EdgeArray;
+use crate::tedges::TEdge;
+use crate::tgraph::{is_isomorphism, TGraph};
+
+// The canonical form of a graph is its relabelling for which the times of the pairs, taken in
+// the order (1,0), (2,0), (2,1), (3,0), ..., are lexicographically the smallest. The vertices
+// are chosen one by one by backtracking, so that the times of the pairs of chosen vertices
+// are known, and the branches that cannot beat the best relabelling found are cut.
+
+struct Search<'a, const N: usize> where Dim<N>: Width {
+    times: &'a EdgeArray<u8, N>,
+    cache: &'a Cache<N>,
+    order: Vec<u8>, // order[k] = vertex labelled k
+    used: [bool; N],
+    key: Vec<u8>,
+    best: Option<(Vec<u8>, Vec<u8>)>, // key and order of the best relabelling
+}
+
+impl<const N: usize> Search<'_, N> where Dim<N>: Width {
+    // Whether exchanging x and y is an automorphism (e.g. for isolated vertices), in which
+    // case only one of them needs to be tried next.
+    fn twins(&self, x: usize, y: usize) -> bool {
+        let e2i = &self.cache.e2i;
+        (0..N).all(|z| z == x || z == y || self.times[e2i[x][z]] == self.times[e2i[y][z]])
+    }
+
+    fn extend(&mut self) {
+        if self.order.len() == N {
+            if self.best.as_ref().is_none_or(|(best, _)| self.key < *best) {
+                self.best = Some((self.key.clone(), self.order.clone()));
+            }
+            return;
+        }
+        for x in 0..N {
+            if self.used[x] || (0..x).any(|y| !self.used[y] && self.twins(x, y)) {
+                continue;
+            }
+            let len = self.key.len();
+            for &y in &self.order {
+                self.key.push(self.times[self.cache.e2i[x][y as usize]]);
+            }
+            if self.best.as_ref().is_none_or(|(best, _)| self.key[..] <= best[..self.key.len()]) {
+                self.used[x] = true;
+                self.order.push(x as u8);
+                self.extend();
+                self.order.pop();
+                self.used[x] = false;
+            }
+            self.key.truncate(len);
+        }
+    }
+}
+
+impl<const N: usize> TGraph<N> where Dim<N>: Width {
+    /// Relabelling of the vertices (vertex v becomes `p[v]`) that gives the canonical form.
+    pub fn canonical_labelling(&self, cache: &Cache<N>) -> Vec<u8> {
+        let mut search = Search { times: &self.times, cache, order: Vec::with_capacity(N), used: [false; N],
+            key: Vec::with_capacity(Self::M), best: None };
+        search.extend();
+        let (_, order) = search.best.unwrap();
+        let mut p = vec![0; N];
+        for (k, &v) in order.iter().enumerate() {
+            p[v as usize] = k as u8;
+        }
+        p
+    }
+
+    /// The graph with vertex v relabelled `p[v]`.
+    pub fn permute(&self, p: &[u8], cache: &Cache<N>) -> TGraph<N> {
+        let tedges: Vec<TEdge> = self.tedges().iter().map(|e| TEdge(p[e.0 as usize], p[e.1 as usize], e.2)).collect();
+        TGraph::from_tedges(N, &tedges, cache).unwrap()
+    }
+
+    /// The same graph for all the graphs isomorphic to this one (with the same times).
+    /// Compare the canonical forms of the representatives to ignore time distortions.
+    pub fn canonical_form(&self, cache: &Cache<N>) -> TGraph<N> {
+        self.permute(&self.canonical_labelling(cache), cache)
+    }
+
+    /// Whether some relabelling of the vertices gives `other` (with the same times).
+    pub fn is_isomorphic(&self, other: &TGraph<N>, cache: &Cache<N>) -> bool {
+        if self.nb_edges != other.nb_edges || self.tmax() != other.tmax() {
+            return false;
+        }
+        // self -> canonical form -> other
+        let p = self.canonical_labelling(cache);
+        let q = other.canonical_labelling(cache);
+        let mut q_inv = vec![0; N];
+        for (v, &k) in q.iter().enumerate() {
+            q_inv[k as usize] = v as u8;
+        }
+        let r: Vec<u8> = p.iter().map(|&k| q_inv[k as usize]).collect();
+        is_isomorphism(&self.times, &other.times, &r, cache)
+    }
+}
+
+#[test]
+pub fn canonical_forms() {
+    use std::collections::HashSet;
+    let cache = crate::cache::compute_cache::<5>();
+    let graphs: Vec<TGraph<5>> = crate::descendants(TGraph::new(), None, &cache).collect();
+    // the generated graphs are pairwise non-isomorphic
+    let forms: HashSet<Vec<u8>> = graphs.iter().map(|g| g.canonical_form(&cache).times.as_slice().to_vec()).collect();
+    assert_eq!(forms.len(), graphs.len());
+    let p = [3, 0, 4, 1, 2];
+    for g in graphs.iter().step_by(11) {
+        let h = g.permute(&p, &cache);
+        assert_eq!(g.canonical_form(&cache).tedges(), h.canonical_form(&cache).tedges());
+        assert!(g.is_isomorphic(&h, &cache));
+    }
+    assert!(!graphs[1].is_isomorphic(&graphs[2], &cache));
+    // highly symmetric graphs on more vertices
+    let cache = crate::cache::compute_cache::<12>();
+    let g = TGraph::<12>::new();
+    assert_eq!(g.canonical_form(&cache).tedges(), g.tedges());
+    let matching: Vec<TEdge> = (0..6).map(|i| TEdge(i, 11 - i, 1)).collect();
+    let g = TGraph::<12>::from_tedges(12, &matching, &cache).unwrap();
+    assert!(g.is_isomorphic(&g.permute(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 0], &cache), &cache));
+}
@@ -0,0 +1,54 @@
+use std::fmt::Write;
+use crate::bits::{Dim, Width};
+use crate::tedges::TEdge;
+use crate::tgraph::TGraph;
+
+impl<const N: usize> TGraph<N> where Dim<N>: Width {
+    /// Graphviz (DOT) drawing of the graph, with the edges labelled by their times.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_highlighting(None, None)
+    }
+
+    /// Same as [`TGraph::to_dot`], drawing in bold red the edges of `spanner` (e.g. those of
+    /// [`TGraph::greedy_spanner`]) and filling the vertex `pivot` (e.g. [`TGraph::pivot_vertex`]).
+    pub fn to_dot_highlighting(&self, spanner: Option<&[TEdge]>, pivot: Option<u8>) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "graph {{").unwrap();
+        writeln!(dot, "    node [shape=circle];").unwrap();
+        for v in 0..N as u8 {
+            if pivot == Some(v) {
+                writeln!(dot, "    {} [style=filled, fillcolor=lightblue];", v).unwrap();
+            } else {
+                writeln!(dot, "    {};", v).unwrap();
+            }
+        }
+        for e in self.tedges() {
+            write!(dot, "    {} -- {} [label=\"{}\"", e.0, e.1, e.2).unwrap();
+            match spanner {
+                Some(spanner) if spanner.contains(e) => write!(dot, ", color=red, penwidth=2").unwrap(),
+                Some(_) => write!(dot, ", color=gray").unwrap(),
+                None => {}
+            }
+            writeln!(dot, "];").unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+#[test]
+pub fn dot_drawing() {
+    let cache = crate::cache::compute_cache::<3>();
+    let g = crate::descendants(TGraph::<3>::new(), None, &cache).find(|g| g.is_clique()).unwrap();
+    let dot = g.to_dot();
+    assert!(dot.starts_with("graph {\n"));
+    assert_eq!(dot.matches(" -- ").count(), 3);
+    for e in g.tedges() {
+        assert!(dot.contains(&format!("    {} -- {} [label=\"{}\"];\n", e.0, e.1, e.2)));
+    }
+    let pivot = g.pivot_vertex();
+    assert!(pivot.is_some());
+    let dot = g.to_dot_highlighting(Some(&g.greedy_spanner()), pivot);
+    assert_eq!(dot.matches("color=red").count(), 3);
+    assert!(dot.contains(&format!("    {} [style=filled", pivot.unwrap())));
+}
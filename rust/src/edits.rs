@@ -0,0 +1,138 @@
+use std::io::{self, BufRead, ErrorKind, Write};
+use crate::bits::{Bits, Dim, EdgeBits, Width};
+use crate::cache::Cache;
+use crate::generation::{Keep, Select};
+use crate::tgraph::TGraph;
+
+// Edit stream of the generation tree: rather than the graphs themselves, the depth-first
+// exploration of the tree is written as one event per line,
+//   push u-v u-v ...   adds a layer (a matching) at the next time, giving a child graph
+//   pop                removes the last layer, going back to the parent graph
+//   emit               the current graph is listed (only with a filter, see `write_edits`)
+// starting from the root of the explored subtree. The pops at the end are omitted.
+
+/// An event of an edit stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edit<const N: usize> where Dim<N>: Width {
+    /// Adds a layer, given as a set of edge indices (see [`Cache::edge`]).
+    Push(EdgeBits<N>),
+    Pop,
+    Emit,
+}
+
+impl<const N: usize> Edit<N> where Dim<N>: Width {
+    /// Writes the event as a line.
+    pub fn write(&self, out: &mut impl Write, cache: &Cache<N>) -> io::Result<()> {
+        match self {
+            Edit::Push(layer) => {
+                write!(out, "push")?;
+                for i in 0..TGraph::<N>::M {
+                    if layer.has(i) {
+                        let e = cache.edge(i);
+                        write!(out, " {}-{}", e.0, e.1)?;
+                    }
+                }
+                writeln!(out)
+            }
+            Edit::Pop => writeln!(out, "pop"),
+            Edit::Emit => writeln!(out, "emit"),
+        }
+    }
+
+    /// Event written by [`Edit::write`] (without the end of line).
+    pub fn parse(line: &str, cache: &Cache<N>) -> Option<Edit<N>> {
+        let mut words = line.split_whitespace();
+        match words.next()? {
+            "push" => {
+                let mut layer = EdgeBits::<N>::ZERO;
+                for word in words {
+                    let (u, v) = word.split_once('-')?;
+                    let (u, v): (u8, u8) = (u.parse().ok()?, v.parse().ok()?);
+                    if u == v || u as usize >= N || v as usize >= N {
+                        return None;
+                    }
+                    layer |= EdgeBits::<N>::bit(cache.edge_index(u, v));
+                }
+                Some(Edit::Push(layer))
+            }
+            "pop" if words.next().is_none() => Some(Edit::Pop),
+            "emit" if words.next().is_none() => Some(Edit::Emit),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the edit stream of the tree explored by [`descendants`](crate::descendants)`(g, select, cache)`.
+/// Without `keep`, every graph of the tree is listed: `g` and the graph obtained after each push.
+/// Otherwise, the graphs listed are those satisfying `keep`, each followed by an `emit` line.
+/// Returns the number of graphs listed.
+pub fn write_edits<const N: usize>(g: TGraph<N>, select: Option<Select<N>>, keep: Option<Keep<N>>, cache: &Cache<N>, mut out: impl Write) -> io::Result<u64> where Dim<N>: Width {
+    let mut nb = 0;
+    let root = g.tmax();
+    let mut depth = root; // tmax of the current graph of the stream
+    let mut stack = vec![g];
+    while let Some(h) = stack.pop() {
+        if select.is_some_and(|select| !select(&h, cache)) {
+            continue;
+        }
+        while depth >= h.tmax() && depth > root {
+            Edit::Pop.write(&mut out, cache)?;
+            depth -= 1;
+        }
+        if h.tmax() > root {
+            Edit::Push(h.layers()[h.tmax() as usize - 1]).write(&mut out, cache)?;
+            depth = h.tmax();
+        }
+        match keep {
+            None => nb += 1,
+            Some(keep) if keep(&h) => {
+                Edit::Emit.write(&mut out, cache)?;
+                nb += 1;
+            }
+            Some(_) => {}
+        }
+        stack.extend(h.successors(cache));
+    }
+    out.flush()?;
+    Ok(nb)
+}
+
+/// Reads the events of an edit stream, one per line.
+pub fn read_edits<'a, const N: usize>(input: impl BufRead + 'a, cache: &'a Cache<N>) -> impl Iterator<Item=io::Result<Edit<N>>> + 'a where Dim<N>: Width {
+    input.lines().map(move |line| {
+        let line = line?;
+        Edit::parse(&line, cache)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, format!("invalid event '{}'", line)))
+    })
+}
+
+#[test]
+pub fn replay_edit_stream() {
+    let cache = crate::cache::compute_cache::<5>();
+    let graphs: Vec<TGraph<5>> = crate::descendants(TGraph::new(), None, &cache).collect();
+    let mut buffer = vec![];
+    assert_eq!(write_edits(TGraph::new(), None, None, &cache, &mut buffer).unwrap(), 15378);
+    // a consumer keeping the current graph on a stack
+    let mut stack = vec![TGraph::<5>::new()];
+    let mut replayed = vec![stack[0].clone()];
+    for edit in read_edits(&buffer[..], &cache) {
+        match edit.unwrap() {
+            Edit::Push(layer) => {
+                let h = stack.last().unwrap().extends_by(&layer, &cache);
+                replayed.push(h.clone());
+                stack.push(h);
+            }
+            Edit::Pop => { stack.pop(); }
+            Edit::Emit => panic!("no filter"),
+        }
+    }
+    assert_eq!(replayed.len(), graphs.len());
+    for (g, h) in graphs.iter().zip(replayed.iter()) {
+        assert_eq!(g.tedges(), h.tedges());
+    }
+    let mut buffer = vec![];
+    assert_eq!(write_edits(TGraph::new(), None, Some(TGraph::is_clique), &cache, &mut buffer).unwrap(), 4524);
+    assert_eq!(read_edits(&buffer[..], &cache).filter(|e| matches!(e, Ok(Edit::Emit))).count(), 4524);
+    assert_eq!(Edit::<5>::parse("push 0-1 2-3", &cache), Some(Edit::Push(EdgeBits::<5>::bit(0) | EdgeBits::<5>::bit(7))));
+    assert_eq!(Edit::<5>::parse("push 0-5", &cache), None);
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use rand::Rng;
+use crate::bits::{Dim, Width};
+use crate::cache::Cache;
+use crate::tgraph::TGraph;
+
+// Knuth's estimator of the size of a tree: going down from the root to a leaf, choosing each
+// time a child uniformly at random, every graph met stands for as many graphs as the product
+// of the numbers of children of its ancestors. The sum over the graphs of the path (restricted
+// to the graphs of interest) is an unbiased estimate of their number in the tree.
+
+/// Mean of independent estimates, with its standard error.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub std_err: f64,
+}
+
+impl Estimate {
+    fn from_sums(nb: u64, sum: f64, sum_sq: f64) -> Estimate {
+        let nb = nb as f64;
+        let mean = sum / nb;
+        let var = if nb > 1.0 { (sum_sq - nb * mean * mean).max(0.0) / (nb - 1.0) } else { f64::INFINITY };
+        Estimate { mean, std_err: (var / nb).sqrt() }
+    }
+
+    /// Approximate 95% confidence interval (normal approximation).
+    pub fn interval95(&self) -> (f64, f64) {
+        (self.mean - 1.96 * self.std_err, self.mean + 1.96 * self.std_err)
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (low, high) = self.interval95();
+        write!(f, "{:.4e} ± {:.2e} (95% CI [{:.4e}, {:.4e}])", self.mean, self.std_err, low.max(0.0), high)
+    }
+}
+
+/// Estimates of the numbers of graphs, of temporally connected graphs and of cliques below a graph.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Estimates {
+    pub probes: u64,
+    pub total: Estimate,
+    pub tc: Estimate,
+    pub cliques: Estimate,
+}
+
+/// One probe from `g` to a leaf: the estimates of the numbers of graphs, of temporally connected
+/// graphs and of cliques in the subtree of `g`.
+pub fn probe<const N: usize>(g: TGraph<N>, rng: &mut impl Rng, cache: &Cache<N>) -> [f64; 3] where Dim<N>: Width {
+    let children = g.successors(cache).collect();
+    probe_below(g, children, rng, cache)
+}
+
+// Same as `probe`, given the children of g
+fn probe_below<const N: usize>(g: TGraph<N>, children: Vec<TGraph<N>>, rng: &mut impl Rng, cache: &Cache<N>) -> [f64; 3] where Dim<N>: Width {
+    let mut res = [0.0; 3];
+    let mut weight = 1.0;
+    let (mut g, mut children) = (g, children);
+    loop {
+        res[0] += weight;
+        if g.is_tc() {
+            res[1] += weight;
+        }
+        if g.is_clique() {
+            res[2] += weight;
+        }
+        if children.is_empty() {
+            return res;
+        }
+        weight *= children.len() as f64;
+        let i = rng.gen_range(0..children.len());
+        g = children.swap_remove(i);
+        children = g.successors(cache).collect();
+    }
+}
+
+/// Estimates from `nb_probes` probes from `g`, see [`probe`].
+pub fn estimate<const N: usize>(g: &TGraph<N>, nb_probes: u64, rng: &mut impl Rng, cache: &Cache<N>) -> Estimates where Dim<N>: Width {
+    let mut sums = [0.0; 3];
+    let mut sums_sq = [0.0; 3];
+    // the children of g are computed once (with their automorphisms, for the empty graph)
+    let children: Vec<TGraph<N>> = g.successors(cache).collect();
+    for _ in 0..nb_probes {
+        let res = probe_below(g.clone(), children.clone(), rng, cache);
+        for k in 0..3 {
+            sums[k] += res[k];
+            sums_sq[k] += res[k] * res[k];
+        }
+    }
+    let [total, tc, cliques] = [0, 1, 2].map(|k| Estimate::from_sums(nb_probes, sums[k], sums_sq[k]));
+    Estimates { probes: nb_probes, total, tc, cliques }
+}
+
+#[test]
+pub fn estimates_for_small_sizes() {
+    use rand::SeedableRng;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let cache = crate::cache::compute_cache::<4>();
+    let est = estimate(&TGraph::<4>::new(), 20000, &mut rng, &cache);
+    for (e, exact) in [(est.total, 62.0), (est.tc, 32.0), (est.cliques, 20.0)] {
+        assert!((e.mean - exact).abs() < 4.0 * e.std_err, "{} vs {}", e, exact);
+    }
+    let cache = crate::cache::compute_cache::<5>();
+    let est = estimate(&TGraph::<5>::new(), 20000, &mut rng, &cache);
+    assert!((est.total.mean - 15378.0).abs() < 4.0 * est.total.std_err);
+    // a path has no branching
+    let est = estimate(&TGraph::<2>::new(), 10, &mut rng, &crate::cache::compute_cache());
+    assert_eq!((est.total.mean, est.total.std_err, est.cliques.mean), (2.0, 0.0, 1.0));
+}
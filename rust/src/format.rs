@@ -0,0 +1,50 @@
+use std::io::{self, Write};
+use crate::bits::{Dim, Width};
+use crate::cache::Cache;
+use crate::stream;
+use crate::tgraph::TGraph;
+
+/// Formats for listing graphs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Timed edges in Rust debug format, one graph per line
+    #[default]
+    Debug,
+    /// Serialization of the Julia version, one graph per line (see [`TGraph::serialize`])
+    Julia,
+    /// Binary stream of matching indices (see [`stream`])
+    Binary,
+    /// JSON Lines, one graph per line (see the `Serialize` implementation of [`TGraph`])
+    Jsonl,
+    /// Graphviz drawings (see [`TGraph::to_dot`]), highlighting the greedy spanner of the
+    /// temporally connected graphs and the pivot vertex, if asked
+    Dot { spanner: bool, pivot: bool },
+}
+
+impl Format {
+    /// Writes what comes before the graphs (only binary streams have a header).
+    pub fn write_header<const N: usize>(&self, out: &mut impl Write, filter: &str) -> io::Result<()> where Dim<N>: Width {
+        match self {
+            Format::Binary => stream::write_header::<N>(out, filter),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes one graph.
+    pub fn write<const N: usize>(&self, out: &mut impl Write, g: &TGraph<N>, cache: &Cache<N>) -> io::Result<()> where Dim<N>: Width {
+        match self {
+            Format::Debug => writeln!(out, "{:?}", g.tedges()),
+            Format::Julia => writeln!(out, "{}", g.serialize()),
+            Format::Binary => stream::write_graph(out, g, cache),
+            Format::Jsonl => {
+                serde_json::to_writer(&mut *out, g)?;
+                writeln!(out)
+            }
+            Format::Dot { spanner, pivot } => {
+                let spanner = (*spanner && g.is_tc()).then(|| g.greedy_spanner());
+                let pivot = if *pivot { g.pivot_vertex() } else { None };
+                write!(out, "{}", g.to_dot_highlighting(spanner.as_deref(), pivot))
+            }
+        }
+    }
+}
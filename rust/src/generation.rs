@@ -0,0 +1,245 @@
+use std::io::{self, Write};
+use crate::bits::{Dim, Width};
+use crate::cache::{Cache, compute_cache};
+use crate::format::Format;
+use crate::memo::MemoCounter;
+use crate::tedges::TEdge;
+use crate::tgraph::TGraph;
+use crate::visitor::{visit, SpannerCheck};
+
+/// Decides whether the subtree of a graph is explored (the graph itself is skipped otherwise).
+pub type Select<const N: usize> = fn(&TGraph<N>, &Cache<N>) -> bool;
+/// Decides whether a graph is counted (or listed).
+pub type Keep<const N: usize> = fn(&TGraph<N>) -> bool;
+
+///////////////// ITERATOR /////////////////////
+
+/// Depth-first iterator over the generation tree below a graph, see [`descendants`].
+pub struct TGraphs<'a, const N: usize> where Dim<N>: Width {
+    stack: Vec<TGraph<N>>,
+    cache: &'a Cache<N>,
+    select: Select<N>,
+}
+
+/// Iterates over `g` and all its descendants in the generation tree, i.e. over all the
+/// STG representatives that extend `g` (over all of them if `g` is `TGraph::new()`).
+/// If `select` is given, the graphs for which it returns false are skipped together with
+/// their subtree.
+pub fn descendants<const N: usize>(g: TGraph<N>, select: Option<Select<N>>, cache: &Cache<N>) -> TGraphs<'_, N> where Dim<N>: Width {
+    match select {
+        None => TGraphs { stack: vec![g], cache, select: |_, _| true },
+        Some(select) => TGraphs { stack: vec![g], cache, select },
+    }
+}
+
+/// Whether `g` has a nontrivial automorphism, as a [`Select`] function: the descendants of the
+/// rigid graphs are rigid, so that their subtrees can be skipped.
+pub fn select_symmetric<const N: usize>(g: &TGraph<N>, _: &Cache<N>) -> bool where Dim<N>: Width {
+    g.has_symmetries()
+}
+
+/// Iterates over the symmetric graphs among `g` and its descendants (see [`descendants`]),
+/// skipping the rigid graphs together with their subtree. There are few of them, so that
+/// they can be enumerated for sizes where the whole tree cannot.
+pub fn descendants_symmetric<const N: usize>(g: TGraph<N>, cache: &Cache<N>) -> TGraphs<'_, N> where Dim<N>: Width {
+    descendants(g, Some(select_symmetric), cache)
+}
+
+impl<const N: usize> Iterator for TGraphs<'_, N> where Dim<N>: Width {
+    type Item = TGraph<N>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(g) = self.stack.pop() {
+            if (self.select)(&g, self.cache) {
+                if g.has_symmetries() {
+                    for h in g.successors_aut(self.cache) {
+                        self.stack.push(h);
+                    }
+                } else {
+                    for h in g.successors_rigid(self.cache) {
+                        self.stack.push(h);
+                    }
+                }
+                return Some(g);
+            }
+        }
+        None
+    }
+}
+
+/// Counts the temporal cliques below `g` that are not dismountable.
+pub fn count_nondismountable<const N: usize>(g: TGraph<N>) -> u64 where Dim<N>: Width {
+    count(g, Some(|g, _| ! g.is_dismountable()), TGraph::is_clique, &compute_cache())
+}
+
+/// Counts the temporal cliques below `g` that have no pivot vertex.
+pub fn count_nonpivotable<const N: usize>(g: TGraph<N>) -> u64 where Dim<N>: Width {
+    count(g, Some(|g, cache| ! g.has_pivot_vertex(cache)), TGraph::is_clique, &compute_cache())
+}
+
+/// Counts the temporal cliques below `g` that are neither dismountable nor pivotable.
+pub fn count_nonboth<const N: usize>(g: TGraph<N>) -> u64 where Dim<N>: Width {
+    count(g, Some(|g, cache| !g.has_pivot_vertex(cache) && !g.is_dismountable()), TGraph::is_clique, &compute_cache())
+}
+
+/// Checks that the temporal cliques below `g` have a spanner of size at most 2n-3, skipping
+/// the subtrees that are known to satisfy it (see [`crate::algos::select`]). Returns the number of cliques
+/// checked, or the first clique for which no such spanner was found.
+pub fn check_spanners<const N: usize>(g: TGraph<N>, cache: &Cache<N>) -> Result<u64, TGraph<N>> where Dim<N>: Width {
+    let mut check = SpannerCheck::new(cache);
+    visit(g, &mut check, cache);
+    check.failure.map_or(Ok(check.nb), Err)
+}
+
+/// Path from the root of the generation tree to the node that represents `g` (which can be any
+/// simple proper temporal graph): the i-th graph of the path is obtained from the previous one by
+/// adding its i-th layer, and the last one is isomorphic to `g.representative(cache)`.
+pub fn locate<const N: usize>(g: &TGraph<N>, cache: &Cache<N>) -> Vec<TGraph<N>> where Dim<N>: Width {
+    let target = g.representative(cache);
+    let mut path = vec![TGraph::new()];
+    for t in 1..=target.tmax() {
+        // the children of a node are pairwise non-isomorphic, and only one of them has a
+        // descendant isomorphic to the target, the one isomorphic to its first t layers
+        let prefix: Vec<TEdge> = target.tedges().iter().copied().filter(|e| e.2 <= t).collect();
+        let prefix = TGraph::from_tedges(N, &prefix, cache).unwrap();
+        let child = path.last().unwrap().successors(cache)
+            .find(|h| h.is_isomorphic(&prefix, cache))
+            .expect("the generation tree contains every representative");
+        path.push(child);
+    }
+    path
+}
+
+/// Index of the node that represents `g` (see [`locate`]) in the order in which
+/// [`descendants`]`(TGraph::new(), None, cache)` iterates over the graphs.
+/// The subtrees on the left of the path to the node are counted with a [`MemoCounter`].
+pub fn rank<const N: usize>(g: &TGraph<N>, cache: &Cache<N>) -> u64 where Dim<N>: Width {
+    let path = locate(g, cache);
+    let mut counter = MemoCounter::new(cache);
+    let mut rank = 0;
+    for pair in path.windows(2) {
+        let (parent, node) = (&pair[0], &pair[1]);
+        rank += 1;
+        // the children are iterated over in the reverse order of the successors
+        let children: Vec<TGraph<N>> = parent.successors(cache).collect();
+        for h in children.into_iter().rev() {
+            if h.tedges() == node.tedges() {
+                break;
+            }
+            rank += counter.count(&h).total as u64;
+        }
+    }
+    rank
+}
+
+/// The graph of index `idx` in the order of [`descendants`]`(TGraph::new(), None, cache)`,
+/// if there are more than `idx` graphs. This is the inverse of [`rank`].
+pub fn unrank<const N: usize>(mut idx: u64, cache: &Cache<N>) -> Option<TGraph<N>> where Dim<N>: Width {
+    let mut g = TGraph::new();
+    let mut counter = MemoCounter::new(cache);
+    'descend: while idx > 0 {
+        idx -= 1;
+        let children: Vec<TGraph<N>> = g.successors(cache).collect();
+        for h in children.into_iter().rev() {
+            let size = counter.count(&h).total as u64;
+            if idx < size {
+                g = h;
+                continue 'descend;
+            }
+            idx -= size;
+        }
+        return None;
+    }
+    Some(g)
+}
+
+////////////////////// GENERATION /////////////////////////
+
+/// Counts `g` and its descendants.
+pub fn count_all<const N: usize>(g: TGraph<N>, cache: &Cache<N>) -> u64 where Dim<N>: Width {
+    descendants(g, None, cache).count() as u64
+}
+
+/// Counts the graphs satisfying `keep` among `g` and its descendants (see [`descendants`]).
+pub fn count<const N: usize>(g: TGraph<N>, select: Option<Select<N>>, keep: Keep<N>, cache: &Cache<N>) -> u64 where Dim<N>: Width {
+    descendants(g, select, cache).filter(keep).count() as u64
+}
+
+/// Same as [`count`], also writing each graph counted in the given format.
+pub fn list_graphs<const N: usize>(g: TGraph<N>, select: Option<Select<N>>, keep: Keep<N>, cache: &Cache<N>, format: Format, mut out: impl Write) -> io::Result<u64> where Dim<N>: Width {
+    let mut nb = 0;
+    for h in descendants(g, select, cache).filter(keep) {
+        format.write(&mut out, &h, cache)?;
+        nb += 1;
+    }
+    out.flush()?;
+    Ok(nb)
+}
+
+#[test]
+pub fn counts_for_small_sizes() {
+    assert_eq!(count_all(TGraph::<1>::new(), &compute_cache()), 1);
+    assert_eq!(count_all(TGraph::<2>::new(), &compute_cache()), 2);
+    assert_eq!(count_all(TGraph::<3>::new(), &compute_cache()), 4);
+    assert_eq!(count_all(TGraph::<4>::new(), &compute_cache()), 62);
+    assert_eq!(count_all(TGraph::<5>::new(), &compute_cache()), 15378);
+}
+
+#[test]
+pub fn cliques_and_tc_for_small_sizes() {
+    let cache = compute_cache::<5>();
+    let graphs: Vec<TGraph<5>> = descendants(TGraph::new(), None, &cache).collect();
+    assert_eq!(graphs.iter().filter(|g| g.is_tc()).count(), 10207);
+    assert_eq!(graphs.iter().filter(|g| g.is_clique()).count(), 4524);
+    let cache = compute_cache::<4>();
+    let graphs: Vec<TGraph<4>> = descendants(TGraph::new(), None, &cache).collect();
+    assert_eq!(graphs.iter().filter(|g| g.is_tc()).count(), 32);
+    assert_eq!(graphs.iter().filter(|g| g.is_clique()).count(), 20);
+}
+
+#[test]
+pub fn symmetric_graphs() {
+    let cache = compute_cache::<5>();
+    let all: Vec<TGraph<5>> = descendants(TGraph::new(), None, &cache).filter(|g| g.has_symmetries()).collect();
+    let symmetric: Vec<TGraph<5>> = descendants_symmetric(TGraph::new(), &cache).collect();
+    assert!(symmetric.iter().map(TGraph::tedges).eq(all.iter().map(TGraph::tedges)));
+    assert_eq!(symmetric.len(), 18);
+    assert_eq!(descendants_symmetric(TGraph::<4>::new(), &compute_cache()).count(), 14);
+}
+
+#[test]
+pub fn first_level_for_larger_sizes() {
+    // the empty graph has one child per size of matching
+    let cache = compute_cache::<9>();
+    assert_eq!(descendants(TGraph::<9>::new(), Some(|g, _| g.tmax() <= 1), &cache).count(), 1 + 4);
+}
+
+#[test]
+pub fn locate_graphs() {
+    let cache = compute_cache::<5>();
+    for g in descendants(TGraph::<5>::new(), None, &cache).step_by(13) {
+        let h = g.permute(&[2, 4, 0, 1, 3], &cache);
+        let path = locate(&h, &cache);
+        assert_eq!(path.len(), g.tmax() as usize + 1);
+        assert_eq!(path.last().unwrap().tedges(), g.tedges());
+    }
+    // G1 of the README is represented by G2
+    let g1 = TGraph::<5>::from_tedges(4, &[TEdge(0, 1, 1), TEdge(0, 2, 2), TEdge(1, 3, 4), TEdge(2, 3, 5)], &cache).unwrap();
+    assert!(locate(&g1, &cache).last().unwrap().is_isomorphic(&g1.representative(&cache), &cache));
+}
+
+#[test]
+pub fn rank_and_unrank() {
+    let cache = compute_cache::<5>();
+    let graphs: Vec<TGraph<5>> = descendants(TGraph::new(), None, &cache).collect();
+    for (i, g) in graphs.iter().enumerate().step_by(97) {
+        assert_eq!(rank(g, &cache), i as u64);
+        assert_eq!(unrank(i as u64, &cache).unwrap().tedges(), g.tedges());
+    }
+    assert_eq!(rank(graphs.last().unwrap(), &cache), 15377);
+    assert!(unrank(15377, &cache).is_some());
+    assert!(unrank(15378, &cache).is_none());
+    let cache = compute_cache::<6>();
+    let last = unrank(89769095, &cache).unwrap();
+    assert_eq!(rank(&last, &cache), 89769095);
+    assert!(unrank(89769096, &cache).is_none());
+}
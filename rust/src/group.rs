@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+use crate::bits::{Dim, Width};
+use crate::tedges::TEdge;
+use crate::tgraph::{vertex_orbit, TGraph};
+
+// The graphs of the generation tree know their automorphisms as long as they have symmetries:
+// the empty graph has all the N! permutations (which are not listed), and every other
+// symmetric graph keeps a generating set of them and their number. A rigid graph only has the
+// identity, and so do its descendants.
+
+/// Automorphism group of a graph, see [`TGraph::automorphism_group`]. A permutation `p` maps
+/// each vertex `v` to `p[v]`.
+#[derive(Clone, Copy, Debug)]
+pub enum AutGroup<'a, const N: usize> where Dim<N>: Width {
+    /// All the permutations of the vertices (group of the empty graph).
+    Symmetric,
+    /// The group generated by some automorphisms, of the given order.
+    Generated { generators: &'a [Vec<u8>], order: u64 },
+    /// Only the identity.
+    Trivial,
+}
+
+fn identity<const N: usize>() -> Vec<u8> {
+    (0..N as u8).collect()
+}
+
+// p ∘ q
+fn compose(p: &[u8], q: &[u8]) -> Vec<u8> {
+    q.iter().map(|&v| p[v as usize]).collect()
+}
+
+// Group generated by gens (by closing the identity under composition with the generators)
+fn closure(gens: &[Vec<u8>], n: usize) -> HashSet<Vec<u8>> {
+    let id: Vec<u8> = (0..n as u8).collect();
+    let mut group = HashSet::from([id.clone()]);
+    let mut stack = vec![id];
+    while let Some(p) = stack.pop() {
+        for s in gens {
+            let q = compose(s, &p);
+            if group.insert(q.clone()) {
+                stack.push(q);
+            }
+        }
+    }
+    group
+}
+
+impl<const N: usize> AutGroup<'_, N> where Dim<N>: Width {
+    /// Number of automorphisms.
+    pub fn order(&self) -> u64 {
+        match self {
+            AutGroup::Symmetric => (1..=N as u64).product(),
+            AutGroup::Generated { order, .. } => *order,
+            AutGroup::Trivial => 1,
+        }
+    }
+
+    /// Whether the identity is the only automorphism.
+    pub fn is_trivial(&self) -> bool {
+        self.order() == 1
+    }
+
+    /// The automorphisms (identity included) in lexicographic order, or None for the group of
+    /// all the permutations, which is not listed.
+    pub fn elements(&self) -> Option<Vec<Vec<u8>>> {
+        match self {
+            AutGroup::Symmetric => None,
+            AutGroup::Generated { generators, .. } => {
+                let mut elements: Vec<Vec<u8>> = closure(generators, N).into_iter().collect();
+                elements.sort_unstable();
+                Some(elements)
+            }
+            AutGroup::Trivial => Some(vec![identity::<N>()]),
+        }
+    }
+
+    /// A small set of automorphisms that generates the group (empty for the trivial group).
+    /// Each generator is not in the group generated by the previous ones.
+    pub fn generators(&self) -> Vec<Vec<u8>> {
+        match self {
+            AutGroup::Symmetric => {
+                // a transposition and a cycle of all the vertices
+                let mut gens = vec![];
+                if N >= 2 {
+                    let mut transposition = identity::<N>();
+                    transposition.swap(0, 1);
+                    gens.push(transposition);
+                }
+                if N >= 3 {
+                    gens.push((1..N as u8).chain([0]).collect());
+                }
+                gens
+            }
+            AutGroup::Generated { generators, .. } => generators.to_vec(),
+            AutGroup::Trivial => vec![],
+        }
+    }
+
+    /// Orbits of the vertices, each sorted, by increasing smallest vertex.
+    pub fn vertex_orbits(&self) -> Vec<Vec<u8>> {
+        match self {
+            AutGroup::Symmetric if N > 0 => vec![identity::<N>()],
+            AutGroup::Symmetric => vec![],
+            AutGroup::Generated { generators, .. } => {
+                let mut orbits: Vec<Vec<u8>> = vec![];
+                for v in 0..N as u8 {
+                    if orbits.iter().all(|orbit| !orbit.contains(&v)) {
+                        let mut orbit = vertex_orbit(v, generators);
+                        orbit.sort_unstable();
+                        orbits.push(orbit);
+                    }
+                }
+                orbits
+            }
+            AutGroup::Trivial => (0..N as u8).map(|v| vec![v]).collect(),
+        }
+    }
+
+    /// Orbits of the edges of `g` (whose group this is), each sorted like [`TGraph::tedges`].
+    /// The edges of an orbit have the same time.
+    pub fn edge_orbits(&self, g: &TGraph<N>) -> Vec<Vec<TEdge>> {
+        let mut orbits: Vec<Vec<TEdge>> = vec![];
+        for e in g.tedges() {
+            if orbits.iter().all(|orbit| !orbit.contains(e)) {
+                let orbit = match self {
+                    AutGroup::Generated { generators, .. } => {
+                        let mut orbit = vec![*e];
+                        let mut i = 0;
+                        while i < orbit.len() {
+                            for p in generators.iter() {
+                                let (u, v) = (p[orbit[i].0 as usize], p[orbit[i].1 as usize]);
+                                let image = g.tedges().iter().find(|f| (f.0, f.1) == (u.min(v), u.max(v))).unwrap();
+                                if !orbit.contains(image) {
+                                    orbit.push(*image);
+                                }
+                            }
+                            i += 1;
+                        }
+                        g.tedges().iter().copied().filter(|f| orbit.contains(f)).collect()
+                    }
+                    // the empty graph has no edges
+                    AutGroup::Symmetric | AutGroup::Trivial => vec![*e],
+                };
+                orbits.push(orbit);
+            }
+        }
+        orbits
+    }
+}
+
+impl<const N: usize> TGraph<N> where Dim<N>: Width {
+    /// Automorphism group of the graph (the permutations of the vertices that preserve the
+    /// time of every pair).
+    pub fn automorphism_group(&self) -> AutGroup<'_, N> {
+        match &self.gens {
+            Some(_) if self.nb_edges == 0 => AutGroup::Symmetric,
+            Some(generators) => AutGroup::Generated { generators, order: self.order },
+            None => AutGroup::Trivial,
+        }
+    }
+}
+
+#[test]
+pub fn automorphism_groups() {
+    let cache = crate::cache::compute_cache::<5>();
+    let root = TGraph::<5>::new();
+    let group = root.automorphism_group();
+    assert_eq!(group.order(), 120);
+    assert_eq!(group.vertex_orbits(), vec![vec![0, 1, 2, 3, 4]]);
+    assert_eq!(closure(&group.generators(), 5).len(), 120);
+    let mut labelled = 0;
+    for g in crate::descendants(TGraph::<5>::new(), None, &cache) {
+        let group = g.automorphism_group();
+        assert_eq!(group.is_trivial(), !g.has_symmetries());
+        if let Some(elements) = group.elements() {
+            assert_eq!(elements.len() as u64, group.order());
+            assert!(elements.iter().all(|p| crate::tgraph::is_automorphism(&g.times, p, &cache)));
+            let generated = closure(&group.generators(), 5);
+            assert!(generated.len() == elements.len() && elements.iter().all(|p| generated.contains(p)));
+        }
+        assert_eq!(group.vertex_orbits().concat().len(), 5);
+        let orbits = group.edge_orbits(&g);
+        assert_eq!(orbits.concat().len(), g.nb_edges());
+        assert!(orbits.iter().all(|orbit| orbit.iter().all(|e| e.2 == orbit[0].2)));
+        labelled += 120 / group.order();
+    }
+    // labelled STGs on 5 vertices (by brute force)
+    assert_eq!(labelled, 1844066);
+    // the square 0-1-2-3 with times 1, 2, 1, 2 has 4 automorphisms
+    let g = TGraph::<5>::from_tedges(4, &[TEdge(0, 1, 1), TEdge(1, 2, 2), TEdge(2, 3, 1), TEdge(0, 3, 2)], &cache).unwrap();
+    let group = g.automorphism_group();
+    assert_eq!(group.order(), 4);
+    assert_eq!(group.generators().len(), 2);
+    assert_eq!(group.vertex_orbits(), vec![vec![0, 1, 2, 3], vec![4]]);
+    assert_eq!(group.edge_orbits(&g).len(), 2);
+}
@@ -1,14 +1,43 @@
-use crate::{M, N};
+use std::ops::{Index, IndexMut};
+use crate::bits::{Bits, Dim, VertexBits, Width};
 use crate::tedges::TEdge;
 
-pub fn gen_edges(n: u8) -> [TEdge; M] {
-    let mut edges: [TEdge; M] = [TEdge(0, 0, 0); M];
-    let mut i = 0;
+pub const fn nb_pairs(n: usize) -> usize {
+    n * (n - 1) / 2
+}
+
+// One slot per edge of the complete graph on N vertices, without dynamic allocation.
+// (Array lengths cannot depend on N*(N-1)/2 in stable Rust, so N*N slots are reserved.)
+#[derive(Clone, Copy, Debug)]
+pub struct EdgeArray<T, const N: usize>([[T; N]; N]);
+
+impl<T: Copy, const N: usize> EdgeArray<T, N> {
+    pub fn new(val: T) -> EdgeArray<T, N> {
+        EdgeArray([[val; N]; N])
+    }
+    pub fn as_slice(&self) -> &[T] {
+        &self.0.as_flattened()[0..nb_pairs(N)]
+    }
+}
+
+impl<T, const N: usize> Index<usize> for EdgeArray<T, N> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {
+        &self.0[i / N][i % N]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for EdgeArray<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i / N][i % N]
+    }
+}
+
+pub fn gen_edges(n: u8) -> Vec<TEdge> {
+    let mut edges: Vec<TEdge> = vec![];
     for u in 0..n {
         for v in (u + 1)..n {
-            edges[i].0 = u;
-            edges[i].1 = v;
-            i += 1;
+            edges.push(TEdge(u, v, 0));
         }
     }
     edges
@@ -22,6 +51,7 @@ pub fn e2i(n: u8, u: u8, v: u8) -> usize {
         ((v * n - (v * (v + 1)) / 2) + u - v - 1) as usize
     }
 }
+#[cfg(test)]
 pub fn i2e(n: u8, mut i: u8) -> TEdge {
     let mut k = 1;
     while i >= (n - k){
@@ -31,62 +61,36 @@ pub fn i2e(n: u8, mut i: u8) -> TEdge {
     TEdge(k - 1, k + i, 0)
 }
 
-pub fn bits_to_indices(edge_bits: u32) -> Vec<usize> {
+pub fn bits_to_indices<B: Bits>(mut edge_bits: B) -> Vec<usize> {
     let mut edge_inds: Vec<usize> = vec![];
-    for i in 0..M {
-        if edge_bits & 1 << i != 0 {
-            edge_inds.push(i);
-        }
+    while edge_bits != B::ZERO {
+        let i = edge_bits.trailing_zeros() as usize;
+        edge_inds.push(i);
+        edge_bits ^= B::bit(i);
     }
     edge_inds
 }
-pub fn indices_to_bits(edge_inds: &[usize]) -> u32 {
-    let mut edge_bits: u32 = 0;
+pub fn indices_to_bits<B: Bits>(edge_inds: &[usize]) -> B {
+    let mut edge_bits = B::ZERO;
     for i in edge_inds {
-        edge_bits |= 1 << *i;
+        edge_bits |= B::bit(*i);
     }
     edge_bits
 }
 
-pub fn powerset<T>(s: &[T]) -> Vec<Vec<T>> where T: Clone {
-    (0..2usize.pow(s.len() as u32)).map(|i| {
-        s.iter().enumerate().filter(|&(t, _)| (i >> t) % 2 == 1)
-            .map(|(_, element)| element.clone())
-            .collect()
-    }).collect()
-}
-
 pub fn are_adjacent(e: &TEdge, f: &TEdge) -> bool {
     (e.0 == f.0) || (e.0 == f.1) || (e.1 == f.0) || (e.1 == f.1)
 }
 
-pub fn edges_to_bits(n: u8, edges: &[TEdge; M]) -> u32 {
-    let mut res: u32 = 0;
-    for e in edges.iter(){
-        let i = e2i(n, e.0, e.1);
-        res |= 1 << i;
-    }
-    return res;
-}
-pub fn bits_to_edges(n: u8, bits: u32) -> Vec<TEdge> { // LALA TEST THIS!
-    let mut res: Vec<TEdge> = vec![];
-    for i in (0..32_u8).into_iter(){
-        if bits & (1 << i) != 0{
-            res.push(i2e(n, i));
-        }
-    }
-    return res;
-}
 
-pub const fn each_ones() -> [u8; N]{
-    let mut preds = [0; N];
-    let mut i = 0;
-    while i < N{
-        preds[i] |= 1 << i;
-        i += 1;
+pub fn each_ones<const N: usize>() -> [VertexBits<N>; N] where Dim<N>: Width {
+    let mut preds = [VertexBits::<N>::ZERO; N];
+    for (i, p) in preds.iter_mut().enumerate() {
+        *p = VertexBits::<N>::bit(i);
     }
     preds
 }
-pub const fn all_ones() -> u8 {
-    (2_u32.pow(N as u32) - 1) as u8
+#[inline]
+pub fn all_ones<const N: usize>() -> VertexBits<N> where Dim<N>: Width {
+    VertexBits::<N>::low(N)
 }
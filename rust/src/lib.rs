@@ -0,0 +1,41 @@
+//! STGen, a generator of simple temporal graphs (STGs) up to isomorphism.
+//!
+//! The graphs are generated as a tree rooted at the empty graph [`TGraph::new`], in which
+//! every child adds a new layer of edges (a matching) at the next time step. The subtrees
+//! are independent, so that any part of the tree can be explored on its own:
+//!
+//! ```
+//! use stgen::{compute_cache, descendants, TGraph};
+//!
+//! let cache = compute_cache::<4>();
+//! let nb_cliques = descendants(TGraph::<4>::new(), None, &cache)
+//!     .filter(|g| g.is_clique())
+//!     .count();
+//! assert_eq!(nb_cliques, 20);
+//! ```
+pub mod bits;
+pub mod tedges;
+mod helper;
+pub mod cache;
+pub mod tgraph;
+pub mod algos;
+pub mod generation;
+pub mod visitor;
+pub mod parallel;
+pub mod batch;
+pub mod serialization;
+pub mod format;
+pub mod stream;
+pub mod edits;
+pub mod dot;
+pub mod tsv;
+pub mod canonical;
+pub mod group;
+pub mod memo;
+pub mod sampling;
+pub mod estimate;
+
+pub use crate::cache::{Cache, compute_cache};
+pub use crate::generation::{descendants, TGraphs};
+pub use crate::tedges::TEdge;
+pub use crate::tgraph::TGraph;
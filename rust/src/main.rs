@@ -1,184 +1,555 @@
-#![allow(dead_code)]
-mod tedges;
-mod helper;
-mod cache;
-mod tgraph;
-mod algos;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use clap::{Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use stgen::bits::{Dim, Width};
+use stgen::batch::Batches;
+use stgen::cache::{compute_cache, Cache};
+use stgen::edits::write_edits;
+use stgen::format::Format;
+use stgen::generation::{check_spanners, list_graphs, locate, rank, select_symmetric, unrank, Keep, Select};
+use stgen::estimate::estimate;
+use stgen::memo::MemoCounter;
+use stgen::sampling::Sampler;
+use stgen::tsv::{nb_vertices_tsv_file, read_tsv_file, LinkStream};
+use stgen::parallel::{check_spanners_par, list_graphs_par, visit_par};
+use stgen::visitor::{visit, AutCounter, Selecting};
+use stgen::TGraph;
 
-const N: usize = 6;
-const M: usize = N * (N - 1) / 2;
-const DNMT: u8 = (2 * N - 3) as u8;
+// Calls `$f::<n>(args)` for a runtime value of n, using the instance compiled for that size.
+// Evaluates to None if no instance exists for n.
+macro_rules! dispatch {
+    ($n:expr, $f:ident($($arg:expr),*)) => {
+        match $n {
+            1 => Some($f::<1>($($arg),*)),
+            2 => Some($f::<2>($($arg),*)),
+            3 => Some($f::<3>($($arg),*)),
+            4 => Some($f::<4>($($arg),*)),
+            5 => Some($f::<5>($($arg),*)),
+            6 => Some($f::<6>($($arg),*)),
+            7 => Some($f::<7>($($arg),*)),
+            8 => Some($f::<8>($($arg),*)),
+            9 => Some($f::<9>($($arg),*)),
+            10 => Some($f::<10>($($arg),*)),
+            11 => Some($f::<11>($($arg),*)),
+            12 => Some($f::<12>($($arg),*)),
+            _ => None,
+        }
+    };
+}
 
-use std::process::exit;
-use rayon::prelude::*;
-use crate::algos::select;
-use crate::cache::{Cache, compute_cache};
-use crate::tgraph::{TGraph};
+const MAX_N: u8 = 12;
 
-fn main() {
-    let n: u8 = N as u8;
-    println!("n={}", n);
-    // let nb = generate(TGraph::new(n), &compute_cache());
-    // let nb = count_all(TGraph::new(n));
-    // let nb = check_spanners(TGraph::new(n), &compute_cache());
-    // let nb = count_nondismountable(TGraph::new(n));
-    // let nb = count_nonpivotable(TGraph::new(n));
-    // let nb = count_nonboth(TGraph::new(n));
-    // let nb = check_spanners_par(TGraph::new(n));
-    let nb = generate_par(TGraph::new(n));
-    println!("Nombre pour n={}: {}", n, nb);
+/// Generates simple temporal graphs (STGs) up to isomorphism.
+/// Without a command, counts all the STGs in parallel.
+#[derive(Parser, Debug)]
+#[command(name = "stgen")]
+struct Cli {
+    /// Number of vertices
+    #[arg(long, global = true, default_value_t = 6, value_parser = clap::value_parser!(u8).range(1..=MAX_N as i64))]
+    n: u8,
+    /// Split the generation tree into independent subtrees explored in parallel
+    #[arg(long, global = true)]
+    par: bool,
+    /// Count by remembering the subtrees below the rigid graphs (count, cliques and tc only, not parallel)
+    #[arg(long, global = true)]
+    memo: bool,
+    /// Maximum number of subtrees remembered by --memo (the smallest ones are forgotten beyond)
+    #[arg(long, global = true)]
+    memo_limit: Option<usize>,
+    /// Only explore the symmetric STGs (with a nontrivial automorphism), skipping the rigid ones with their subtree (counting and listing commands)
+    #[arg(long, global = true)]
+    symmetric: bool,
+    /// Number of threads for the parallel version (implies --par)
+    #[arg(long, global = true)]
+    threads: Option<usize>,
+    /// Write the listed graphs to this file instead of the standard output
+    #[arg(long, short, global = true)]
+    output: Option<PathBuf>,
+    /// Format of the listed graphs
+    #[arg(long, global = true, value_enum, default_value_t = FormatArg::Debug)]
+    format: FormatArg,
+    /// What to highlight in the drawings of --format dot
+    #[arg(long, global = true, value_enum, value_delimiter = ',')]
+    highlight: Vec<Highlight>,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum FormatArg {
+    /// Timed edges, one graph per line
+    Debug,
+    /// Serialization of the Julia version (see julia/serialization.jl), one graph per line
+    Julia,
+    /// JSON Lines: n, tmax, timed edges and automorphism group (generators and order), one graph per line
+    Jsonl,
+    /// Compact binary stream (requires --output)
+    Binary,
+    /// Depth-first edit stream of the generation tree ("push u-v ..." / "pop" lines), never parallel
+    Edits,
+    /// Graphviz drawings, with the edges labelled by their times
+    Dot,
+}
 
-///////////////// ITERATOR /////////////////////
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum Highlight {
+    /// A spanner of the temporally connected graphs (found greedily)
+    Spanner,
+    /// The pivot vertex, if any
+    Pivot,
+}
 
-pub struct TGraphs<'a> {
-    stack: Vec<TGraph>,
-    cache: &'a Cache,
-    select: fn(&TGraph, &Cache) -> bool,
+impl Cli {
+    // Format of the graphs written one by one (edit streams are written by write_edits, for
+    // listing commands only)
+    fn graph_format(&self) -> io::Result<Format> {
+        Ok(match self.format {
+            FormatArg::Debug => Format::Debug,
+            FormatArg::Julia => Format::Julia,
+            FormatArg::Binary => Format::Binary,
+            FormatArg::Jsonl => Format::Jsonl,
+            FormatArg::Edits => return Err(io::Error::new(io::ErrorKind::InvalidInput, "--format edits only applies to the listing of the generation tree")),
+            FormatArg::Dot => Format::Dot {
+                spanner: self.highlight.contains(&Highlight::Spanner),
+                pivot: self.highlight.contains(&Highlight::Pivot),
+            },
+        })
+    }
 }
 
-pub fn descendants(g: TGraph, select: Option<fn(&TGraph, &Cache) -> bool>, cache: &Cache) -> TGraphs {
-    if select.is_none() {
-        TGraphs { stack: vec![g], cache, select: |_, _| true }
-    } else {
-        TGraphs { stack: vec![g], cache, select: select.unwrap() }
-    }
-}
-
-impl<'a> Iterator for TGraphs<'a>{
-    type Item = TGraph;
-    fn next(&mut self) -> Option<Self::Item> {
-        while ! self.stack.is_empty() {
-            let g = self.stack.pop().unwrap();
-            if (self.select)(&g, &self.cache) {
-                if g.has_symmetries() {
-                    for h in g.successors_aut(&self.cache) {
-                        self.stack.push(h);
-                    }
-                } else {
-                    for h in g.successors_rigid(&self.cache) {
-                        self.stack.push(h);
-                    }
-                }
-                return Some(g);
-            }
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+enum Command {
+    /// Count all STGs
+    Count,
+    /// List all STGs
+    List,
+    /// Count temporal cliques
+    Cliques {
+        /// Also list them
+        #[arg(long)]
+        list: bool,
+    },
+    /// Count temporally connected STGs
+    Tc {
+        /// Also list them
+        #[arg(long)]
+        list: bool,
+    },
+    /// Check that every temporal clique has a spanner of size at most 2n-3
+    CheckSpanners,
+    /// Count temporal cliques that are not dismountable
+    Nondismountable {
+        /// Also list them
+        #[arg(long)]
+        list: bool,
+    },
+    /// Count temporal cliques that have no pivot vertex
+    Nonpivotable {
+        /// Also list them
+        #[arg(long)]
+        list: bool,
+    },
+    /// Run the tests on a temporal graph read from a file of "u v t" lines (n is its number of vertices)
+    Analyze {
+        file: PathBuf,
+    },
+    /// Find the node of the generation tree that represents a graph read from a file of "u v t" lines
+    Locate {
+        file: PathBuf,
+    },
+    /// Index of the node that represents a graph read from a file of "u v t" lines, in the order of `list`
+    Rank {
+        file: PathBuf,
+    },
+    /// Write the graph of the given index in the order of `list`
+    Unrank {
+        index: u64,
+    },
+    /// Estimate the numbers of STGs, temporally connected STGs and temporal cliques (Knuth's estimator)
+    Estimate {
+        /// Number of random probes from the root to a leaf
+        #[arg(long, default_value_t = 10000)]
+        probes: u64,
+        /// Seed of the random generator (random if not given)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Write the number of batches of the generation tree, which can be run independently with run-batch
+    Batches {
+        /// The batches are the subtrees of the STGs with at least this number of edges (n by default, as in the C++ version)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..))]
+        cut: Option<u8>,
+    },
+    /// Count the STGs of one batch (see batches), writing a summary as a JSON object
+    RunBatch {
+        /// Index of the batch, from 0
+        #[arg(long)]
+        batch: usize,
+        /// Same as for batches
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..))]
+        cut: Option<u8>,
+    },
+    /// Write graphs chosen uniformly at random (independently), up to n=7
+    Sample {
+        /// Number of graphs
+        #[arg(long, default_value_t = 1)]
+        samples: u64,
+        /// Seed of the random generator (random if not given)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+// What --memo counts
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Counted {
+    All,
+    Cliques,
+    Tc,
+}
+
+impl Command {
+    // Whether the command enumerates the generation tree (see `run`), rather than working on a
+    // single graph or on a part of the tree (see `run_one`)
+    fn is_enumeration(&self) -> bool {
+        match self {
+            Command::Count | Command::List | Command::Cliques { .. } | Command::Tc { .. } | Command::CheckSpanners
+                | Command::Nondismountable { .. } | Command::Nonpivotable { .. } => true,
+            Command::Analyze { .. } | Command::Locate { .. } | Command::Rank { .. } | Command::Unrank { .. }
+                | Command::Estimate { .. } | Command::Sample { .. } | Command::Batches { .. } | Command::RunBatch { .. } => false,
         }
-        None
     }
-}
 
-pub fn count_nondismountable(g: TGraph) -> u64 {
-    let mut nb = 0;
-    let cache = compute_cache();
-    for h in descendants(g, Some(|g, _| ! g.is_dismountable()), &cache){
-        if h.is_clique() {
-            nb += 1;
+    // Whether the command writes its results to --output (or else to the standard output),
+    // e.g. the listed graphs
+    fn writes_output(&self) -> bool {
+        match *self {
+            Command::List | Command::Unrank { .. } | Command::Sample { .. } | Command::Batches { .. } | Command::RunBatch { .. } => true,
+            Command::Cliques { list } | Command::Tc { list } | Command::Nondismountable { list } | Command::Nonpivotable { list } => list,
+            _ => false,
+        }
+    }
+
+    // What the command counts, if it can be counted with --memo
+    fn counted_by_memo(&self) -> Option<Counted> {
+        match *self {
+            Command::Count => Some(Counted::All),
+            Command::Cliques { list: false } => Some(Counted::Cliques),
+            Command::Tc { list: false } => Some(Counted::Tc),
+            _ => None,
         }
     }
-    nb
 }
 
-pub fn count_nonpivotable(g: TGraph) -> u64 {
-    let mut nb = 0;
-    let cache = compute_cache();
-    for h in descendants(g, Some(|g, cache| ! g.has_pivot_vertex(cache)), &cache){
-        if h.is_clique() {
-            nb += 1;
+fn main() {
+    let mut cli = Cli::parse();
+    if cli.command.is_none() {
+        cli.command = Some(Command::Count);
+        cli.par = !cli.memo;
+    }
+    if cli.threads.is_some() {
+        cli.par = true;
+    }
+    if let Err(e) = check_options(&cli) {
+        eprintln!("error: {}", e);
+        exit(2);
+    }
+    if let Some(threads) = cli.threads {
+        if let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global() {
+            eprintln!("error: {}", e);
+            exit(2);
+        }
+    }
+    let out: Box<dyn Write + Send> = match &cli.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("error: cannot create {}: {}", path.display(), e);
+                exit(2);
+            }
+        },
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    // a graph to analyze has as many vertices as in its file, rather than isolated ones added up to n
+    if let Some(Command::Analyze { file }) = &cli.command {
+        match nb_vertices_tsv_file(file) {
+            Ok(nb) if nb <= MAX_N as usize => cli.n = nb.max(1) as u8,
+            Ok(nb) => {
+                eprintln!("error: {}: {} vertices, at most {} are supported", file.display(), nb, MAX_N);
+                exit(1);
+            }
+            Err(e) => {
+                eprintln!("error: {}: {}", file.display(), e);
+                exit(1);
+            }
+        }
+    }
+    // the standard output only has the results, if any (e.g. valid JSON Lines)
+    let quiet = cli.output.is_none() && cli.command.as_ref().unwrap().writes_output();
+    let status = |line: String| if quiet { eprintln!("{}", line) } else { println!("{}", line) };
+    status(format!("n={}", cli.n));
+    let command = cli.command.as_ref().unwrap();
+    if !command.is_enumeration() {
+        if let Err(e) = dispatch!(cli.n, run_one(&cli, out)).unwrap() {
+            eprintln!("error: {}", e);
+            exit(1);
+        }
+        return;
+    }
+    match dispatch!(cli.n, run(&cli, out)).unwrap() {
+        Ok(nb) => status(format!("Nombre pour n={}: {}", cli.n, nb)),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            exit(1);
         }
     }
-    nb
 }
 
-pub fn count_nonboth(g: TGraph) -> u64 {
-    let mut nb = 0;
-    let cache = compute_cache();
-    for h in descendants(g, Some(|g, cache| !g.has_pivot_vertex(cache) && !g.is_dismountable()), &cache){
-        if h.is_clique() {
-            nb += 1;
+// Rejects the combinations of options that some command would not apply, before running it
+fn check_options(cli: &Cli) -> Result<(), &'static str> {
+    let command = cli.command.as_ref().unwrap();
+    if cli.format == FormatArg::Binary && cli.output.is_none() {
+        return Err("--format binary requires --output");
+    }
+    if cli.memo_limit.is_some() && !cli.memo {
+        return Err("--memo-limit requires --memo");
+    }
+    if cli.memo {
+        if command.counted_by_memo().is_none() {
+            return Err("--memo only applies to count, cliques and tc, without listing");
+        }
+        if cli.symmetric {
+            return Err("--memo counts all the graphs, not only the symmetric ones");
+        }
+        if cli.par {
+            return Err("--memo is not parallel (no --par or --threads)");
         }
     }
-    nb
+    if cli.symmetric && (!command.is_enumeration() || *command == Command::CheckSpanners) {
+        return Err("--symmetric only applies to the counting and listing commands");
+    }
+    Ok(())
 }
 
-pub fn check_spanners(g: TGraph, cache: &Cache) -> u64 {
-    let mut nb = 0;
-    for h in descendants(g, Some(select), cache){
-        if h.is_clique(){
-            nb += 1;
-            if !h.has_optimal_spanner(1000){
-                println!("FAILING ON:");
-                println!("{:?}", h.tedges());
-                exit(0);
-            }
+fn run<const N: usize>(cli: &Cli, mut out: Box<dyn Write + Send>) -> io::Result<u128> where Dim<N>: Width {
+    let symmetric = cli.symmetric;
+    let command = cli.command.as_ref().unwrap();
+    let (select, keep, list, filter): (Option<Select<N>>, Keep<N>, bool, &str) = match *command {
+        Command::Count => (symmetric.then_some(select_symmetric), |_| true, false, "all"),
+        Command::List => (symmetric.then_some(select_symmetric), |_| true, true, "all"),
+        Command::Cliques { list } => (symmetric.then_some(select_symmetric), TGraph::is_clique, list, "cliques"),
+        Command::Tc { list } => (symmetric.then_some(select_symmetric), TGraph::is_tc, list, "tc"),
+        Command::CheckSpanners => return Ok(run_check_spanners::<N>(cli).into()),
+        Command::Nondismountable { list } => (Some(if symmetric {
+            |g: &TGraph<N>, _: &Cache<N>| g.has_symmetries() && !g.is_dismountable()
+        } else {
+            |g, _| !g.is_dismountable()
+        }), TGraph::is_clique, list, "nondismountable"),
+        Command::Nonpivotable { list } => (Some(if symmetric {
+            |g: &TGraph<N>, cache: &Cache<N>| g.has_symmetries() && !g.has_pivot_vertex(cache)
+        } else {
+            |g, cache| !g.has_pivot_vertex(cache)
+        }), TGraph::is_clique, list, "nonpivotable"),
+        _ => unreachable!("see Command::is_enumeration"),
+    };
+    let g = TGraph::<N>::new();
+    if cli.memo {
+        let counted = command.counted_by_memo().expect("see check_options");
+        let cache = compute_cache::<N>();
+        let mut counter = if counted == Counted::Tc { MemoCounter::with_reachability(&cache) } else { MemoCounter::new(&cache) };
+        if let Some(max) = cli.memo_limit {
+            counter = counter.remember_at_most(max);
         }
+        let counts = counter.count(&g);
+        let nb = match counted {
+            Counted::All => counts.total,
+            Counted::Cliques => counts.cliques,
+            Counted::Tc => counts.tc.unwrap(),
+        };
+        // the symmetric graphs are few, the others are rigid
+        let mut symmetric = Selecting::new(Some(select_symmetric), &cache, AutCounter::new(keep));
+        visit(g, &mut symmetric, &cache);
+        let mut orders = symmetric.inner;
+        *orders.by_order.entry(1).or_default() += nb - orders.nb();
+        print_orders(&orders);
+        return Ok(nb);
     }
-    nb
+    if list && cli.format == FormatArg::Edits {
+        let keep = (*command != Command::List).then_some(keep);
+        return write_edits(g, select, keep, &compute_cache(), out).map(u128::from);
+    }
+    if list {
+        cli.graph_format()?.write_header::<N>(&mut out, filter)?;
+    }
+    let cache = compute_cache::<N>();
+    let nb = match (cli.par, list) {
+        (par, false) => {
+            let mut counter = Selecting::new(select, &cache, AutCounter::new(keep));
+            if par {
+                visit_par(g, &mut counter, &cache);
+            } else {
+                visit(g, &mut counter, &cache);
+            }
+            print_orders(&counter.inner);
+            return Ok(counter.inner.nb());
+        }
+        (false, true) => list_graphs(g, select, keep, &cache, cli.graph_format()?, out)?,
+        (true, true) => list_graphs_par(g, select, keep, cli.graph_format()?, out)?,
+    };
+    Ok(nb.into())
 }
 
-////////////////////// GENERATION /////////////////////////
+// Labelled graphs and numbers of graphs by order of their automorphism group
+fn print_orders<const N: usize>(orders: &AutCounter<N>) where Dim<N>: Width {
+    println!("labelled: {}", orders.nb_labelled());
+    for (order, nb) in &orders.by_order {
+        println!("|Aut| = {}: {}", order, nb);
+    }
+}
 
-pub fn count_all(g: TGraph, cache: &Cache) -> u64{
-    let mut nb = 0;
-    for _ in descendants(g, None, &cache){
-        nb += 1;
+// Commands that do not enumerate the generation tree
+fn run_one<const N: usize>(cli: &Cli, mut out: Box<dyn Write + Send>) -> Result<(), Box<dyn Error>> where Dim<N>: Width {
+    let cache = compute_cache::<N>();
+    let read = |file: &Path| read_tsv_file(file, &cache).map_err(|e| format!("{}: {}", file.display(), e));
+    match cli.command.as_ref().unwrap() {
+        Command::Analyze { file } => analyze(read(file)?, &cache),
+        Command::Locate { file } => {
+            let node = locate(&read(file)?.graph, &cache).pop().unwrap();
+            // matchings added along the path from the root
+            for (t, layer) in &node.tedges().iter().group_by(|e| e.2) {
+                println!("{}:{}", t, layer.map(|e| format!(" {}-{}", e.0, e.1)).collect::<String>());
+            }
+        }
+        Command::Rank { file } => println!("rank: {}", rank(&read(file)?.graph, &cache)),
+        Command::Unrank { index } => match unrank::<N>(*index, &cache) {
+            Some(g) => {
+                let format = cli.graph_format()?;
+                format.write_header::<N>(&mut out, "all")?;
+                format.write(&mut out, &g, &cache)?;
+                out.flush()?;
+            }
+            None => return Err(format!("no graph of index {}", index).into()),
+        },
+        Command::Estimate { probes, seed } => {
+            let est = estimate(&TGraph::<N>::new(), *probes, &mut rng(*seed), &cache);
+            println!("probes: {}", est.probes);
+            println!("STGs: {}", est.total);
+            println!("temporally connected: {}", est.tc);
+            println!("cliques: {}", est.cliques);
+        }
+        Command::Sample { samples, seed } => {
+            let mut rng = rng(*seed);
+            let mut sampler = Sampler::new(&cache);
+            let format = cli.graph_format()?;
+            format.write_header::<N>(&mut out, "sample")?;
+            for _ in 0..*samples {
+                format.write(&mut out, &sampler.sample(&mut rng), &cache)?;
+            }
+            out.flush()?;
+        }
+        Command::Batches { cut } => {
+            writeln!(out, "{}", Batches::with_cut(cut.unwrap_or(N as u8), &cache).number_of_batches())?;
+            out.flush()?;
+        }
+        Command::RunBatch { batch, cut } => {
+            let batches = Batches::with_cut(cut.unwrap_or(N as u8), &cache);
+            if *batch >= batches.number_of_batches() {
+                return Err(format!("no batch {} (there are {} batches)", batch, batches.number_of_batches()).into());
+            }
+            writeln!(out, "{}", serde_json::to_string(&batches.summarize(*batch))?)?;
+            out.flush()?;
+        }
+        _ => unreachable!("see Command::is_enumeration"),
     }
-    nb
+    Ok(())
 }
 
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
 
-///////////////// PARALLEL VERSIONS //////////////////////
+fn analyze<const N: usize>(stream: LinkStream<N>, cache: &Cache<N>) where Dim<N>: Width {
+    let g = &stream.graph;
+    println!("edges: {}, times: {}", g.nb_edges(), g.tmax());
+    println!("representative: {:?}", g.representative(cache).tedges());
+    let group = g.automorphism_group();
+    let orbits = group.vertex_orbits().iter()
+        .filter(|orbit| orbit.len() > 1)
+        .map(|orbit| format!("{{{}}}", orbit.iter().map(|&v| stream.vertices[v as usize]).join(", ")))
+        .join(" ");
+    println!("automorphisms: {} (nontrivial vertex orbits: {})", group.order(), if orbits.is_empty() { "none" } else { &orbits });
+    println!("clique: {}", g.is_clique());
+    println!("temporally connected: {}", g.is_tc());
+    println!("dismountable: {}", g.is_dismountable());
+    match g.pivot_vertex() {
+        Some(v) => println!("pivot vertex: {}", stream.vertices[v as usize]),
+        None => println!("pivot vertex: none"),
+    }
+    if g.is_tc() {
+        println!("greedy spanner size: {} (2n-3 = {})", g.greedy_spanner_size(), TGraph::<N>::DNMT);
+    }
+}
 
-// Splits the work into chunks to be done in parallel
-pub fn get_pool(g: TGraph) -> (Vec<TGraph>, usize) {
-    const TCUT: u8 = 5;
-    let mut pool = vec![];
-    let mut nbdropped = 0;
-    let cache = compute_cache();
-    for h in descendants(g, Some(|k, _| k.nb_edges <= TCUT), &cache).into_iter(){
-        for hh in h.successors(&cache){
-            if hh.nb_edges > TCUT{
-                pool.push(hh);
+// Stops the program on the first clique without an optimal spanner
+fn run_check_spanners<const N: usize>(cli: &Cli) -> u64 where Dim<N>: Width {
+    let res = if cli.par {
+        check_spanners_par(TGraph::<N>::new())
+    } else {
+        check_spanners(TGraph::<N>::new(), &compute_cache())
+    };
+    match res {
+        Ok(nb) => nb,
+        Err(h) => {
+            println!("FAILING ON:");
+            match cli.graph_format() {
+                Ok(format @ (Format::Dot { .. } | Format::Julia | Format::Jsonl)) => {
+                    format.write(&mut io::stdout(), &h, &compute_cache()).unwrap();
+                }
+                _ => println!("{:?}", h.tedges()),
             }
+            exit(0);
         }
-        nbdropped += 1;
     }
-    (pool, nbdropped)
 }
 
-pub fn generate_par(g: TGraph) -> usize {
-    let cache: Cache = compute_cache();
-    let pool = get_pool(g);
-    let npool = pool.0;
-    // let pool_size = npool.len();
-    // println!("{} pieces in total", pool_size);
-    let nb: u64 = npool.into_par_iter().enumerate()
-        .map(|(_, h)| { // change _ with i for printing progress
-            let mut res = 0;
-            for _ in descendants(h, None, &cache){
-                res += 1;
-                // println!("{} / {}", pool_size, i + 1); // print progress in terminal
-            }
-            res
-        })
-        .sum();
-    println!();
-    return nb as usize + pool.1;
-}
-
-pub fn check_spanners_par(g: TGraph) -> usize {
-    let cache: Cache = compute_cache();
-    let pool = get_pool(g);
-    let npool = pool.0;
-    // let pool_size = npool.len();
-    // println!("{} pieces in total", pool_size);
-    let nb: u64 = npool.into_par_iter().enumerate()
-        .map(|(_, h)| { // change _ with i for printing
-            let res = check_spanners(h, &cache);
-            // println!("{} / {}", pool_size, i + 1); // uncomment for progression in terminal
-            res
-        })
-        .sum();
-    println!();
-    return nb as usize + pool.1;
+#[test]
+pub fn parse_command_line() {
+    let cli = Cli::try_parse_from(["stgen"]).unwrap();
+    assert_eq!((cli.n, cli.par, cli.command), (6, false, None));
+    let cli = Cli::try_parse_from(["stgen", "cliques", "--n", "4", "--list", "--threads", "2"]).unwrap();
+    assert_eq!((cli.n, cli.threads, cli.command), (4, Some(2), Some(Command::Cliques { list: true })));
+    let cli = Cli::try_parse_from(["stgen", "--par", "-o", "out.txt", "check-spanners"]).unwrap();
+    assert_eq!(cli.format, FormatArg::Debug);
+    assert_eq!((cli.par, cli.output, cli.command), (true, Some(PathBuf::from("out.txt")), Some(Command::CheckSpanners)));
+    assert!(Cli::try_parse_from(["stgen", "--n", "13"]).is_err());
+    assert!(Cli::try_parse_from(["stgen", "--n", "0"]).is_err());
+    assert!(Cli::try_parse_from(["stgen", "unknown"]).is_err());
+    assert_eq!(Cli::try_parse_from(["stgen", "list", "--format", "julia"]).unwrap().format, FormatArg::Julia);
+    assert_eq!(Cli::try_parse_from(["stgen", "list", "--format", "jsonl"]).unwrap().format, FormatArg::Jsonl);
+    assert_eq!(Cli::try_parse_from(["stgen", "list", "--format", "edits"]).unwrap().format, FormatArg::Edits);
+    let cli = Cli::try_parse_from(["stgen", "check-spanners", "--format", "dot", "--highlight", "spanner,pivot"]).unwrap();
+    assert_eq!(cli.graph_format().unwrap(), Format::Dot { spanner: true, pivot: true });
+    let cli = Cli::try_parse_from(["stgen", "analyze", "graph.tsv", "--n", "8"]).unwrap();
+    assert_eq!(cli.command, Some(Command::Analyze { file: PathBuf::from("graph.tsv") }));
+    assert_eq!(Cli::try_parse_from(["stgen", "unrank", "42"]).unwrap().command, Some(Command::Unrank { index: 42 }));
+    assert!(Cli::try_parse_from(["stgen", "count", "--symmetric", "--n", "8"]).unwrap().symmetric);
+    assert_eq!(Cli::try_parse_from(["stgen", "run-batch", "--n", "7", "--batch", "3"]).unwrap().command, Some(Command::RunBatch { batch: 3, cut: None }));
+    assert!(Cli::try_parse_from(["stgen", "run-batch"]).is_err());
+    assert_eq!(dispatch!(13, count_root()), None);
+    assert_eq!(dispatch!(4, count_root()), Some(62));
+}
+
+#[cfg(test)]
+fn count_root<const N: usize>() -> u64 where Dim<N>: Width {
+    stgen::generation::count_all(TGraph::<N>::new(), &compute_cache())
 }
+
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::ops::AddAssign;
+use crate::bits::{Bits, Dim, EdgeBits, VertexBits, Width};
+use crate::cache::Cache;
+use crate::tgraph::TGraph;
+
+// Below a rigid graph, the subtree depends only on the edges of the graph and on its candidate
+// edges for the next layer (the edges adjacent to its last layer), not on the earlier times.
+// So do the numbers of graphs and of cliques in the subtree. The number of temporally connected
+// graphs also depends on the reachability between the vertices, which is then added to the key.
+
+/// Numbers of graphs in a subtree of the generation tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub total: u128,
+    pub cliques: u128,
+    /// Only counted with [`MemoCounter::with_reachability`].
+    pub tc: Option<u128>,
+}
+
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Counts) {
+        self.total += other.total;
+        self.cliques += other.cliques;
+        self.tc = self.tc.zip(other.tc).map(|(a, b)| a + b);
+    }
+}
+
+// Very small subtrees are counted again rather than remembered, to save memory. When the memo
+// is full, the smallest subtrees remembered are forgotten, and only larger ones are remembered.
+const MIN_REMEMBERED: u128 = 4;
+
+type Key<const N: usize> = (EdgeBits<N>, EdgeBits<N>, [VertexBits<N>; N]);
+
+/// Exact counter of the graphs below a graph, which remembers the counts below the rigid graphs.
+pub struct MemoCounter<'a, const N: usize> where Dim<N>: Width {
+    cache: &'a Cache<N>,
+    reachability: bool,
+    memo: HashMap<Key<N>, [u64; 3]>, // total, cliques and tc (if counted), to save memory
+    min_remembered: u128,
+    max_remembered: usize,
+}
+
+impl<'a, const N: usize> MemoCounter<'a, N> where Dim<N>: Width {
+    /// Counter of all the graphs and of the cliques.
+    pub fn new(cache: &'a Cache<N>) -> MemoCounter<'a, N> {
+        MemoCounter { cache, reachability: false, memo: HashMap::new(), min_remembered: MIN_REMEMBERED, max_remembered: usize::MAX }
+    }
+
+    /// Counter of all the graphs, of the cliques and of the temporally connected graphs
+    /// (which remembers more subtrees).
+    pub fn with_reachability(cache: &'a Cache<N>) -> MemoCounter<'a, N> {
+        MemoCounter { reachability: true, ..MemoCounter::new(cache) }
+    }
+
+    /// Remembers at most `max` subtrees (the largest ones), which bounds the memory used.
+    pub fn remember_at_most(mut self, max: usize) -> MemoCounter<'a, N> {
+        self.max_remembered = max;
+        self
+    }
+
+    /// Numbers of subtrees remembered.
+    pub fn nb_remembered(&self) -> usize {
+        self.memo.len()
+    }
+
+    /// Counts `g` and its descendants.
+    pub fn count(&mut self, g: &TGraph<N>) -> Counts {
+        let mut counts = Counts {
+            total: 1,
+            cliques: g.is_clique() as u128,
+            tc: self.reachability.then(|| g.is_tc() as u128),
+        };
+        if g.has_symmetries() {
+            for h in g.successors_aut(self.cache) {
+                counts += self.count(&h);
+            }
+            return counts;
+        }
+        let preds = if self.reachability { g.predecessors() } else { [VertexBits::<N>::ZERO; N] };
+        let key = (g.edges_bits(), g.cand_bits(), preds);
+        if let Some(&[total, cliques, tc]) = self.memo.get(&key) {
+            return Counts { total: total.into(), cliques: cliques.into(), tc: self.reachability.then_some(tc.into()) };
+        }
+        for h in g.successors_rigid(self.cache) {
+            counts += self.count(&h);
+        }
+        if counts.total >= self.min_remembered && self.memo.len() >= self.max_remembered {
+            self.forget_smallest();
+        }
+        if counts.total >= self.min_remembered {
+            if let [Ok(total), Ok(cliques), Ok(tc)] = [counts.total, counts.cliques, counts.tc.unwrap_or(0)].map(u64::try_from) {
+                self.memo.insert(key, [total, cliques, tc]);
+            }
+        }
+        counts
+    }
+
+    // Forgets (at least) the smallest half of the subtrees remembered, or more if they are large
+    fn forget_smallest(&mut self) {
+        while self.memo.len() >= self.max_remembered / 2 && !self.memo.is_empty() {
+            self.min_remembered *= 2;
+            let min = self.min_remembered;
+            self.memo.retain(|_, &mut [total, _, _]| u128::from(total) >= min);
+        }
+    }
+}
+
+#[test]
+pub fn memoized_counts() {
+    let cache = crate::cache::compute_cache::<5>();
+    let counts = MemoCounter::new(&cache).count(&TGraph::new());
+    assert_eq!(counts, Counts { total: 15378, cliques: 4524, tc: None });
+    let counts = MemoCounter::with_reachability(&cache).count(&TGraph::new());
+    assert_eq!(counts, Counts { total: 15378, cliques: 4524, tc: Some(10207) });
+    let cache4 = crate::cache::compute_cache::<4>();
+    assert_eq!(MemoCounter::with_reachability(&cache4).count(&TGraph::new()).tc, Some(32));
+    let mut counter = MemoCounter::with_reachability(&cache).remember_at_most(100);
+    assert_eq!(counter.count(&TGraph::new()), Counts { total: 15378, cliques: 4524, tc: Some(10207) });
+    assert!(counter.nb_remembered() < 100);
+    assert_eq!(MemoCounter::new(&cache).remember_at_most(usize::MAX).count(&TGraph::new()).total, 15378);
+    let cache = crate::cache::compute_cache::<6>();
+    // a subtree on 6 vertices, with symmetric and rigid graphs, by brute force
+    use crate::generation::{count, count_all, descendants};
+    let g = descendants(TGraph::<6>::new(), None, &cache).find(|g| g.nb_edges() == 6 && g.has_symmetries()).unwrap();
+    let counts = MemoCounter::with_reachability(&cache).count(&g);
+    assert_eq!(counts.total, count_all(g.clone(), &cache).into());
+    assert_eq!(counts.cliques, count(g.clone(), None, TGraph::is_clique, &cache).into());
+    assert_eq!(counts.tc, Some(count(g.clone(), None, TGraph::is_tc, &cache).into()));
+}
+
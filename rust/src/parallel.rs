@@ -0,0 +1,132 @@
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use rayon::prelude::*;
+use crate::bits::{Dim, Width};
+use crate::cache::{Cache, compute_cache};
+use crate::format::Format;
+use crate::generation::{count, list_graphs, Keep, Select};
+use crate::tgraph::TGraph;
+use crate::visitor::{visit_until, Control, SpannerCheck, Visitor};
+
+///////////////// PARALLEL VERSIONS //////////////////////
+
+/// By default, the subtrees of the graphs with more than `TCUT` edges are explored in parallel.
+pub const TCUT: u8 = 5;
+
+/// Splits the work into chunks to be done in parallel, rooted at the graphs with more than `cut`
+/// edges. Returns the roots of the chunks and the graphs above them (already selected), which
+/// are not part of any chunk. Both are listed in a deterministic order.
+pub fn get_pool<const N: usize>(g: TGraph<N>, select: Option<Select<N>>, cut: u8, cache: &Cache<N>) -> (Vec<TGraph<N>>, Vec<TGraph<N>>) where Dim<N>: Width {
+    let mut pool = vec![];
+    let mut top = vec![];
+    let mut stack = vec![g];
+    while let Some(h) = stack.pop() {
+        if h.nb_edges > cut {
+            pool.push(h);
+        } else if select.is_none_or(|select| select(&h, cache)) {
+            stack.extend(h.successors(cache));
+            top.push(h);
+        }
+    }
+    (pool, top)
+}
+
+/// Parallel version of [`count`].
+pub fn count_par<const N: usize>(g: TGraph<N>, select: Option<Select<N>>, keep: Keep<N>) -> u64 where Dim<N>: Width {
+    let cache: Cache<N> = compute_cache();
+    let (pool, top) = get_pool(g, select, TCUT, &cache);
+    let nb: u64 = pool.into_par_iter()
+        .map(|h| count(h, select, keep, &cache))
+        .sum();
+    nb + top.iter().filter(|h| keep(h)).count() as u64
+}
+
+/// Parallel version of [`list_graphs`], except that the graphs are not listed in a deterministic order.
+pub fn list_graphs_par<const N: usize>(g: TGraph<N>, select: Option<Select<N>>, keep: Keep<N>, format: Format, mut out: impl Write + Send) -> io::Result<u64> where Dim<N>: Width {
+    let cache: Cache<N> = compute_cache();
+    let (pool, top) = get_pool(g, select, TCUT, &cache);
+    let mut nb = 0;
+    for h in top.iter().filter(|h| keep(h)) {
+        format.write(&mut out, h, &cache)?;
+        nb += 1;
+    }
+    let out = Mutex::new(out);
+    nb += pool.into_par_iter()
+        .map(|h| {
+            let mut buffer = vec![];
+            let nb = list_graphs(h, select, keep, &cache, format, &mut buffer)?;
+            out.lock().unwrap().write_all(&buffer)?;
+            Ok(nb)
+        })
+        .sum::<io::Result<u64>>()?;
+    out.into_inner().unwrap().flush()?;
+    Ok(nb)
+}
+
+/// Counts `g` and its descendants in parallel.
+pub fn generate_par<const N: usize>(g: TGraph<N>) -> usize where Dim<N>: Width {
+    count_par(g, None, |_| true) as usize
+}
+
+/// Parallel version of [`crate::visitor::visit`]. The graphs above the chunks (see [`get_pool`])
+/// are visited first by `visitor`, then each thread visits chunks with its own
+/// [`Visitor::fork`] of it, and the forks are merged into `visitor`. Returns false if the
+/// traversal was stopped (the other threads then stop as soon as possible, so which graphs
+/// were visited depends on the timing).
+pub fn visit_par<const N: usize, V: Visitor<N> + Send + Sync>(g: TGraph<N>, visitor: &mut V, cache: &Cache<N>) -> bool where Dim<N>: Width {
+    visit_par_cut(g, visitor, TCUT, cache)
+}
+
+/// Same as [`visit_par`], with chunks rooted at the graphs with more than `cut` edges.
+pub fn visit_par_cut<const N: usize, V: Visitor<N> + Send + Sync>(g: TGraph<N>, visitor: &mut V, cut: u8, cache: &Cache<N>) -> bool where Dim<N>: Width {
+    let mut pool = vec![];
+    let mut stack = vec![g];
+    while let Some(h) = stack.pop() {
+        if h.nb_edges > cut {
+            pool.push(h);
+            continue;
+        }
+        match visitor.visit(&h) {
+            Control::Descend => stack.extend(h.successors(cache)),
+            Control::Skip => {}
+            Control::Stop => return false,
+        }
+    }
+    let stop = AtomicBool::new(false);
+    let forks = pool.into_par_iter()
+        .fold(|| visitor.fork(), |mut v, h| {
+            visit_until(h, &mut v, cache, &stop);
+            v
+        })
+        .reduce(|| visitor.fork(), |mut a, b| {
+            a.merge(b);
+            a
+        });
+    visitor.merge(forks);
+    !stop.into_inner()
+}
+
+/// Parallel version of [`crate::generation::check_spanners`].
+pub fn check_spanners_par<const N: usize>(g: TGraph<N>) -> Result<u64, TGraph<N>> where Dim<N>: Width {
+    let cache: Cache<N> = compute_cache();
+    let mut check = SpannerCheck::new(&cache);
+    visit_par(g, &mut check, &cache);
+    check.failure.map_or(Ok(check.nb), Err)
+}
+
+#[test]
+pub fn parallel_and_filtered_counts() {
+    assert_eq!(count_par(TGraph::<5>::new(), None, |_| true), 15378);
+    assert_eq!(count_par(TGraph::<5>::new(), None, TGraph::is_tc), 10207);
+    let cache = compute_cache::<5>();
+    let nondismountable = crate::generation::count_nondismountable(TGraph::<5>::new());
+    assert_eq!(count_par(TGraph::<5>::new(), Some(|g, _| !g.is_dismountable()), TGraph::is_clique), nondismountable);
+    let mut listed = vec![];
+    assert_eq!(list_graphs_par(TGraph::<5>::new(), None, TGraph::is_clique, Format::Julia, &mut listed).unwrap(), 4524);
+    assert_eq!(listed.iter().filter(|&&c| c == b'\n').count(), 4524);
+    assert_eq!(crate::generation::check_spanners(TGraph::<5>::new(), &cache).ok(), check_spanners_par(TGraph::<5>::new()).ok());
+    let mut counter = crate::visitor::Counter::new(TGraph::is_clique);
+    assert!(visit_par(TGraph::<5>::new(), &mut counter, &cache));
+    assert_eq!(counter.nb, 4524);
+}
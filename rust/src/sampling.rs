@@ -0,0 +1,80 @@
+use rand::Rng;
+use crate::bits::{Dim, Width};
+use crate::cache::Cache;
+use crate::memo::MemoCounter;
+use crate::tgraph::TGraph;
+
+/// Uniform sampler of the graphs of the generation tree. A graph is drawn by going down the tree
+/// from the root, stopping at a graph with probability one over the size of its subtree, and
+/// otherwise moving to a child with probability proportional to the size of its subtree.
+/// The sizes of the subtrees are computed once and remembered (see [`MemoCounter`]).
+///
+/// The first draw counts the whole tree: for N = 7, this takes about a minute and a half and
+/// 1 GB of memory, and N = 8 is out of reach (see [`crate::estimate`] for approximate counts).
+pub struct Sampler<'a, const N: usize> where Dim<N>: Width {
+    cache: &'a Cache<N>,
+    counter: MemoCounter<'a, N>,
+}
+
+impl<'a, const N: usize> Sampler<'a, N> where Dim<N>: Width {
+    pub fn new(cache: &'a Cache<N>) -> Sampler<'a, N> {
+        Sampler { cache, counter: MemoCounter::new(cache) }
+    }
+
+    /// Number of graphs in the subtree of `g` (including `g`).
+    pub fn subtree_size(&mut self, g: &TGraph<N>) -> u128 {
+        self.counter.count(g).total
+    }
+
+    /// A graph of the subtree of `g` (including `g`), chosen uniformly at random.
+    pub fn sample_below(&mut self, g: TGraph<N>, rng: &mut impl Rng) -> TGraph<N> {
+        let mut g = g;
+        let mut r = rng.gen_range(0..self.subtree_size(&g));
+        'descend: while r > 0 {
+            r -= 1;
+            let children: Vec<TGraph<N>> = g.successors(self.cache).collect();
+            for h in children {
+                let size = self.subtree_size(&h);
+                if r < size {
+                    g = h;
+                    continue 'descend;
+                }
+                r -= size;
+            }
+            unreachable!("the subtree sizes add up");
+        }
+        g
+    }
+
+    /// A graph on N vertices (an STG representative), chosen uniformly at random.
+    pub fn sample(&mut self, rng: &mut impl Rng) -> TGraph<N> {
+        self.sample_below(TGraph::new(), rng)
+    }
+}
+
+/// A graph on N vertices (an STG representative), chosen uniformly at random.
+/// Use a [`Sampler`] to draw several graphs, which computes the subtree sizes only once.
+pub fn sample_uniform<const N: usize>(rng: &mut impl Rng, cache: &Cache<N>) -> TGraph<N> where Dim<N>: Width {
+    Sampler::new(cache).sample(rng)
+}
+
+#[test]
+pub fn uniform_samples() {
+    use std::collections::HashMap;
+    use rand::SeedableRng;
+    let cache = crate::cache::compute_cache::<5>();
+    let mut sampler = Sampler::new(&cache);
+    assert_eq!(sampler.subtree_size(&TGraph::new()), 15378);
+    let cache4 = crate::cache::compute_cache::<4>();
+    let mut sampler = Sampler::new(&cache4);
+    assert_eq!(sampler.subtree_size(&TGraph::new()), 62);
+    let mut rng = rand::rngs::StdRng::seed_from_u64(2020);
+    let mut nb: HashMap<Vec<(u8, u8, u8)>, u32> = HashMap::new();
+    for _ in 0..62 * 200 {
+        let g = sampler.sample(&mut rng);
+        *nb.entry(g.tedges().iter().map(|e| (e.0, e.1, e.2)).collect()).or_default() += 1;
+    }
+    // each graph is drawn 200 times on average
+    assert_eq!(nb.len(), 62);
+    assert!(nb.values().all(|&k| (120..=280).contains(&k)));
+}
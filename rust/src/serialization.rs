@@ -0,0 +1,277 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use crate::bits::{Bits, Dim, EdgeBits, Width};
+use crate::cache::Cache;
+use crate::generation::descendants;
+use crate::tgraph::TGraph;
+
+// Serialization of the Julia version (see julia/serialization.jl): the times of the pairs of
+// vertices (0 for non-edges), taken in the order (1,2), (1,3), ..., (2,3), ..., are the digits
+// of one big integer in base M+1, starting from the digit of weight (M+1)^1.
+// The integer is written in decimal.
+//
+// The Julia version computes this integer as an Int64, which wraps around from 2^63. Up to 6
+// vertices, the integer is smaller than (M+1)^(M+1) <= 2^64, so that it is written here as
+// wrapped by Julia (a negative number when it is at least 2^63), and such negative numbers are
+// read back. From 7 vertices, the integers written by the Julia version may have overflowed
+// and cannot be read (while those written here are exact, and thus differ).
+
+// Largest number of pairs for which the integers are smaller than 2^64
+const MAX_WRAPPED_PAIRS: usize = 15;
+
+const LIMB: u64 = 1_000_000_000; // big integers are little-endian vectors of base 10^9 digits
+
+fn mul_add(limbs: &mut Vec<u64>, mul: u64, add: u64) {
+    let mut carry = add;
+    for limb in limbs.iter_mut() {
+        let x = *limb * mul + carry;
+        *limb = x % LIMB;
+        carry = x / LIMB;
+    }
+    while carry > 0 {
+        limbs.push(carry % LIMB);
+        carry /= LIMB;
+    }
+}
+
+fn div_rem(limbs: &mut Vec<u64>, div: u64) -> u64 {
+    let mut rem = 0;
+    for limb in limbs.iter_mut().rev() {
+        let x = rem * LIMB + *limb;
+        *limb = x / div;
+        rem = x % div;
+    }
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    rem
+}
+
+fn encode(times: &[u8]) -> String {
+    let base = times.len() as u64 + 1;
+    let mut limbs = vec![];
+    for t in times.iter().rev() {
+        mul_add(&mut limbs, base, *t as u64);
+    }
+    mul_add(&mut limbs, base, 0);
+    if times.len() <= MAX_WRAPPED_PAIRS && limbs.len() == 3 {
+        let value = (limbs[2] * LIMB + limbs[1]) as u128 * LIMB as u128 + limbs[0] as u128;
+        if value >= 1 << 63 {
+            return (value as u64 as i64).to_string(); // as in Julia
+        }
+    }
+    match limbs.split_last() {
+        None => "0".to_string(),
+        Some((last, rest)) => {
+            let mut s = last.to_string();
+            for limb in rest.iter().rev() {
+                s += &format!("{:09}", limb);
+            }
+            s
+        }
+    }
+}
+
+fn decode(s: &str, m: usize) -> Result<Vec<u8>, DeserializeError> {
+    let s = s.trim();
+    let unwrapped;
+    let s = if s.starts_with('-') {
+        if m > MAX_WRAPPED_PAIRS {
+            return Err(DeserializeError::Overflowed);
+        }
+        match s.parse::<i64>() {
+            Ok(value) if value < 0 => {
+                unwrapped = (value as u64).to_string();
+                &unwrapped
+            }
+            _ => return Err(DeserializeError::InvalidNumber),
+        }
+    } else {
+        s
+    };
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(DeserializeError::InvalidNumber);
+    }
+    let mut limbs = vec![];
+    for chunk in s.as_bytes().chunks(9) {
+        let digits = std::str::from_utf8(chunk).unwrap();
+        mul_add(&mut limbs, 10_u64.pow(digits.len() as u32), digits.parse().unwrap());
+    }
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    let base = m as u64 + 1;
+    div_rem(&mut limbs, base); // weight 1, unused
+    let times: Vec<u8> = (0..m).map(|_| div_rem(&mut limbs, base) as u8).collect();
+    if !limbs.is_empty() {
+        return Err(DeserializeError::TooLarge);
+    }
+    Ok(times)
+}
+
+/// Error when reading a serialized graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeserializeError {
+    /// The string is not a decimal integer (negative ones are only valid up to 6 vertices).
+    InvalidNumber,
+    /// The integer is negative, because it has overflowed in the Julia version (from 7 vertices).
+    Overflowed,
+    /// The integer has more digits than there are pairs of vertices.
+    TooLarge,
+    /// The times are not those of a generated STG (some time is missing,
+    /// or two adjacent edges have the same time).
+    InvalidTimes,
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeserializeError::InvalidNumber => write!(f, "not a valid integer"),
+            DeserializeError::Overflowed => write!(f, "negative integer (overflowed in the Julia version)"),
+            DeserializeError::TooLarge => write!(f, "too large for this number of vertices"),
+            DeserializeError::InvalidTimes => write!(f, "times do not form a sequence of matchings"),
+        }
+    }
+}
+
+impl Error for DeserializeError {}
+
+impl<const N: usize> TGraph<N> where Dim<N>: Width {
+    /// Encoding of the graph as in the Julia version (base M+1 digits, written in decimal).
+    pub fn serialize(&self) -> String {
+        encode(self.times.as_slice())
+    }
+
+    /// Graph encoded by [`TGraph::serialize`] (or by the Julia version).
+    pub fn deserialize(s: &str, cache: &Cache<N>) -> Result<TGraph<N>, DeserializeError> {
+        let times = decode(s, Self::M)?;
+        let mut g = TGraph::new();
+        for t in 1..=times.iter().copied().max().unwrap_or(0) {
+            let mut layer = EdgeBits::<N>::ZERO;
+            for (i, _) in times.iter().enumerate().filter(|(_, &ti)| ti == t) {
+                if (cache.adjacent_bits[i] & layer) != EdgeBits::<N>::ZERO {
+                    return Err(DeserializeError::InvalidTimes);
+                }
+                layer |= EdgeBits::<N>::bit(i);
+            }
+            if layer == EdgeBits::<N>::ZERO {
+                return Err(DeserializeError::InvalidTimes);
+            }
+            g = g.extends_by(&layer, cache);
+        }
+        Ok(g)
+    }
+}
+
+// With serde, a graph is a structure with fields `n`, `tmax` and `edges` (the timed edges in
+// chronological order), and, if the graph has symmetries, `generators` (automorphisms that
+// generate the group, rather than all of them, which can be millions) and `order`.
+impl<const N: usize> Serialize for TGraph<N> where Dim<N>: Width {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let group = self.has_symmetries().then(|| self.automorphism_group());
+        let mut state = serializer.serialize_struct("TGraph", 3 + 2 * group.is_some() as usize)?;
+        state.serialize_field("n", &self.n())?;
+        state.serialize_field("tmax", &self.tmax())?;
+        state.serialize_field("edges", self.tedges())?;
+        if let Some(group) = group {
+            state.serialize_field("generators", &group.generators())?;
+            state.serialize_field("order", &group.order())?;
+        } else {
+            state.skip_field("generators")?;
+            state.skip_field("order")?;
+        }
+        state.end()
+    }
+}
+
+/// Writes the graphs, serialized, one per line.
+pub fn write_graphs<const N: usize>(mut out: impl Write, graphs: impl IntoIterator<Item=TGraph<N>>) -> io::Result<u64> where Dim<N>: Width {
+    let mut nb = 0;
+    for g in graphs {
+        writeln!(out, "{}", g.serialize())?;
+        nb += 1;
+    }
+    out.flush()?;
+    Ok(nb)
+}
+
+/// Reads graphs written by [`write_graphs`] (one per line, empty lines are skipped).
+pub fn read_graphs<'a, const N: usize>(input: impl BufRead + 'a, cache: &'a Cache<N>) -> impl Iterator<Item=io::Result<TGraph<N>>> + 'a where Dim<N>: Width {
+    input.lines()
+        .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .map(move |line| {
+            let line = line?;
+            TGraph::deserialize(&line, cache)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("'{}': {}", line, e)))
+        })
+}
+
+/// Writes all temporal cliques on N vertices to the file `CLIQUES-N`, like `write_cliques`
+/// in the Julia version. Returns the number of cliques.
+pub fn write_cliques<const N: usize>(dir: impl AsRef<Path>, cache: &Cache<N>) -> io::Result<u64> where Dim<N>: Width {
+    let file = File::create(dir.as_ref().join(format!("CLIQUES-{}", N)))?;
+    let cliques = descendants(TGraph::new(), None, cache).filter(|g| g.is_clique());
+    write_graphs(BufWriter::new(file), cliques)
+}
+
+/// Reads all the graphs of a file written by [`write_graphs`] or [`write_cliques`].
+pub fn read_file<const N: usize>(path: impl AsRef<Path>, cache: &Cache<N>) -> io::Result<Vec<TGraph<N>>> where Dim<N>: Width {
+    read_graphs(BufReader::new(File::open(path)?), cache).collect()
+}
+
+#[test]
+pub fn same_encoding_as_julia() {
+    // test_serialize() in julia/serialization.jl (edges (1,3,1), (1,2,2), (2,4,3), (4,5,5), (3,5,5), (1,4,6))
+    let times = [2, 1, 6, 0, 0, 3, 0, 0, 5, 5];
+    assert_eq!(encode(&times), "141482184272");
+    assert_eq!(decode("141482184272", 10), Ok(times.to_vec()));
+    assert_eq!(encode(&[]), "0");
+    assert_eq!(decode("12a", 10), Err(DeserializeError::InvalidNumber));
+    assert_eq!(decode("11", 1), Err(DeserializeError::TooLarge));
+    // on 6 vertices, with time 8 on the last pair (4,5), the integer wraps around in Julia
+    // (its exact value is 9241512880845620496)
+    let mut times = [0; 15];
+    for (i, t) in [(0, 1), (5, 2), (9, 3), (12, 4), (1, 5), (6, 6), (10, 7), (14, 8)] {
+        times[i] = t;
+    }
+    assert_eq!(encode(&times), "-9205231192863931120");
+    assert_eq!(decode("-9205231192863931120", 15), Ok(times.to_vec()));
+    assert_eq!(decode("9241512880845620496", 15), Ok(times.to_vec()));
+    let g = TGraph::<6>::deserialize("-9205231192863931120", &crate::cache::compute_cache()).unwrap();
+    assert_eq!((g.tmax(), g.time(4, 5), g.serialize()), (8, Some(8), "-9205231192863931120".to_string()));
+    times[14] = 7;
+    assert_eq!(encode(&times), (9241512880845620496_u64 - (1 << 60)).to_string());
+    assert_eq!(decode("-1", 21), Err(DeserializeError::Overflowed));
+    assert_eq!(decode("-0", 15), Err(DeserializeError::InvalidNumber));
+}
+
+#[test]
+pub fn serialize_round_trip() {
+    let cache = crate::cache::compute_cache::<6>();
+    let graphs: Vec<TGraph<6>> = descendants(TGraph::new(), None, &cache).take(5000).collect();
+    let mut buffer = vec![];
+    assert_eq!(write_graphs(&mut buffer, graphs.clone()).unwrap(), 5000);
+    let read: Vec<TGraph<6>> = read_graphs(&buffer[..], &cache).collect::<io::Result<_>>().unwrap();
+    for (g, h) in graphs.iter().zip(read.iter()) {
+        assert_eq!(g.tedges(), h.tedges());
+        assert_eq!(g.has_symmetries(), h.has_symmetries());
+    }
+    // both (0,1) and (0,2) at time 1
+    assert_eq!(TGraph::<6>::deserialize(&encode(&[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]), &cache).err(),
+               Some(DeserializeError::InvalidTimes));
+}
+
+#[test]
+pub fn json_fields() {
+    let cache = crate::cache::compute_cache::<4>();
+    let json: Vec<String> = descendants(TGraph::<4>::new(), None, &cache)
+        .map(|g| serde_json::to_string(&g).unwrap())
+        .collect();
+    assert_eq!(json[0], r#"{"n":4,"tmax":0,"edges":[],"generators":[[1,0,2,3],[1,2,3,0]],"order":24}"#);
+    assert_eq!(json[1], r#"{"n":4,"tmax":1,"edges":[[0,3,1],[1,2,1]],"generators":[[0,2,1,3],[1,0,3,2]],"order":8}"#);
+    assert!(json.iter().all(|s| !s.contains('\n')));
+}
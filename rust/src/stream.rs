@@ -0,0 +1,158 @@
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+use std::path::Path;
+use crate::bits::{Bits, Dim, EdgeBits, Width};
+use crate::cache::Cache;
+use crate::tgraph::TGraph;
+
+// Binary stream of graphs. The header is made of the magic bytes, the version of the format,
+// the number of vertices and the name of the filter used to select the graphs (its length on
+// one byte, then its bytes). Each graph is then stored as its number of layers (one byte),
+// followed by the index of each layer in `Cache::matchings`, little-endian, on the smallest
+// number of bytes that can hold all the indices for this number of vertices (see `index_width`).
+
+/// Magic bytes at the beginning of a binary stream.
+pub const MAGIC: [u8; 4] = *b"STGB";
+/// Current version of the binary stream format.
+pub const VERSION: u8 = 1;
+
+/// Header of a binary stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub n: u8,
+    /// Name of the filter used to select the graphs (e.g. "cliques"), empty if none.
+    pub filter: String,
+}
+
+fn index_width<const N: usize>(cache: &Cache<N>) -> usize where Dim<N>: Width {
+    match cache.indicators.len() {
+        0..=0x100 => 1,
+        0x101..=0x10000 => 2,
+        _ => 4,
+    }
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg.into())
+}
+
+/// Writes the header of a stream of graphs on N vertices.
+pub fn write_header<const N: usize>(out: &mut impl Write, filter: &str) -> io::Result<()> where Dim<N>: Width {
+    if filter.len() > u8::MAX as usize {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "filter name too long"));
+    }
+    out.write_all(&MAGIC)?;
+    out.write_all(&[VERSION, N as u8, filter.len() as u8])?;
+    out.write_all(filter.as_bytes())
+}
+
+/// Writes one graph of a stream (after [`write_header`]).
+pub fn write_graph<const N: usize>(out: &mut impl Write, g: &TGraph<N>, cache: &Cache<N>) -> io::Result<()> where Dim<N>: Width {
+    let width = index_width(cache);
+    let mut record = Vec::with_capacity(1 + width * g.tmax() as usize);
+    record.push(g.tmax());
+    for layer in g.layers() {
+        let index = cache.matching_index(layer).expect("the layers of a graph are matchings") as u32;
+        record.extend_from_slice(&index.to_le_bytes()[..width]);
+    }
+    out.write_all(&record)
+}
+
+/// Writes a whole stream. Returns the number of graphs.
+pub fn write_stream<const N: usize>(mut out: impl Write, filter: &str, graphs: impl IntoIterator<Item=TGraph<N>>, cache: &Cache<N>) -> io::Result<u64> where Dim<N>: Width {
+    write_header::<N>(&mut out, filter)?;
+    let mut nb = 0;
+    for g in graphs {
+        write_graph(&mut out, &g, cache)?;
+        nb += 1;
+    }
+    out.flush()?;
+    Ok(nb)
+}
+
+/// Reader of a binary stream of graphs on N vertices, iterating over the graphs.
+pub struct StreamReader<'a, R: Read, const N: usize> where Dim<N>: Width {
+    input: R,
+    header: Header,
+    cache: &'a Cache<N>,
+    width: usize,
+}
+
+impl<'a, R: Read, const N: usize> StreamReader<'a, R, N> where Dim<N>: Width {
+    /// Reads the header, which must be that of a stream of graphs on N vertices.
+    pub fn new(mut input: R, cache: &'a Cache<N>) -> io::Result<Self> {
+        let mut start = [0; 7];
+        input.read_exact(&mut start)?;
+        if start[..4] != MAGIC {
+            return Err(invalid_data("not a binary stream of graphs"));
+        }
+        let (version, n) = (start[4], start[5]);
+        if version != VERSION {
+            return Err(invalid_data(format!("unsupported version {}", version)));
+        }
+        if n as usize != N {
+            return Err(invalid_data(format!("graphs on {} vertices, expected {}", n, N)));
+        }
+        let mut filter = vec![0; start[6] as usize];
+        input.read_exact(&mut filter)?;
+        let filter = String::from_utf8(filter).map_err(|_| invalid_data("invalid filter name"))?;
+        Ok(StreamReader { input, header: Header { version, n, filter }, cache, width: index_width(cache) })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    fn read_graph(&mut self, nb_layers: u8) -> io::Result<TGraph<N>> {
+        let mut g = TGraph::new();
+        for _ in 0..nb_layers {
+            let mut bytes = [0; 4];
+            self.input.read_exact(&mut bytes[..self.width])?;
+            let index = u32::from_le_bytes(bytes) as usize;
+            let layer = self.cache.indicators.get(index)
+                .filter(|&&layer| layer & g.edges_bits == EdgeBits::<N>::ZERO)
+                .ok_or_else(|| invalid_data(format!("invalid matching index {}", index)))?;
+            g = g.extends_by(layer, self.cache);
+        }
+        Ok(g)
+    }
+}
+
+impl<R: Read, const N: usize> Iterator for StreamReader<'_, R, N> where Dim<N>: Width {
+    type Item = io::Result<TGraph<N>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut nb_layers = [0];
+        match self.input.read(&mut nb_layers) {
+            Ok(0) => None,
+            Ok(_) => Some(self.read_graph(nb_layers[0])),
+            Err(e) if e.kind() == ErrorKind::Interrupted => self.next(),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Opens a file written with [`write_stream`] (or `stgen --format binary`).
+pub fn open_stream<const N: usize>(path: impl AsRef<Path>, cache: &Cache<N>) -> io::Result<StreamReader<'_, BufReader<File>, N>> where Dim<N>: Width {
+    StreamReader::new(BufReader::new(File::open(path)?), cache)
+}
+
+#[test]
+pub fn stream_round_trip() {
+    let cache = crate::cache::compute_cache::<5>();
+    let graphs: Vec<TGraph<5>> = crate::generation::descendants(TGraph::new(), None, &cache).collect();
+    let mut buffer = vec![];
+    assert_eq!(write_stream(&mut buffer, "all", graphs.clone(), &cache).unwrap(), 15378);
+    let reader = StreamReader::new(&buffer[..], &cache).unwrap();
+    assert_eq!(reader.header(), &Header { version: VERSION, n: 5, filter: "all".to_string() });
+    let read: Vec<TGraph<5>> = reader.collect::<io::Result<_>>().unwrap();
+    assert_eq!(read.len(), graphs.len());
+    for (g, h) in graphs.iter().zip(read.iter()) {
+        assert_eq!(g.tedges(), h.tedges());
+        assert_eq!(g.has_symmetries(), h.has_symmetries());
+    }
+    // wrong number of vertices, truncated graph
+    assert!(StreamReader::<_, 4>::new(&buffer[..], &crate::cache::compute_cache()).is_err());
+    let mut reader = StreamReader::new(&buffer[..buffer.len() - 1], &cache).unwrap();
+    assert!(reader.nth(15377).unwrap().is_err());
+}
@@ -1,4 +1,8 @@
-#[derive(Clone,Copy,Debug, Eq)]
+use serde::{Deserialize, Serialize};
+
+/// A timed edge (u, v, t). Edges are equal if they have the same endpoints and time.
+/// It is serialized as the array `[u, v, t]`.
+#[derive(Clone,Copy,Debug, Eq, Serialize, Deserialize)]
 pub struct TEdge (pub u8, pub u8, pub u8);
 impl PartialEq for TEdge {
     fn eq(&self, other: &Self) -> bool {
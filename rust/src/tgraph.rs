@@ -1,40 +1,40 @@
-use itertools::{Either, Itertools};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use itertools::Either;
 use itertools::Either::Left;
 use itertools::Either::Right;
-use crate::helper::{all_ones, bits_to_indices, indices_to_bits};
-use crate::{M, N};
+use crate::bits::{Bits, Dim, EdgeBits, VertexBits, Width};
+use crate::helper::{all_ones, bits_to_indices, e2i, each_ones, indices_to_bits, nb_pairs, EdgeArray};
 use crate::cache::{Cache, get_indicators};
 use crate::tedges::TEdge;
 
+/// Incremental reachability information, updated edge by edge in chronological order.
 #[derive(Clone, Debug)]
-pub struct Reachability {
-    pub(crate) preds: [u8; N], // who reached this vertex
-    pub(crate) preds2: [u8; N], // who reached this vertex after pivoting
+pub struct Reachability<const N: usize> where Dim<N>: Width {
+    pub(crate) preds: [VertexBits<N>; N], // who reached this vertex
+    pub(crate) preds2: [VertexBits<N>; N], // who reached this vertex after pivoting
 }
 
-impl Reachability {
-    pub fn new(n: u8) -> Reachability {
-        let mut preds = [0_u8; N];
-        for i in 0..n as usize {
-            preds[i] |= 1 << i;
-        }
-        Reachability{preds, preds2: [0_u8; N]}
+impl<const N: usize> Reachability<N> where Dim<N>: Width {
+    pub fn new() -> Reachability<N> {
+        Reachability{preds: each_ones::<N>(), preds2: [VertexBits::<N>::ZERO; N]}
     }
 
     pub fn update(&mut self, e: TEdge){
         let u = e.0 as usize;
         let v = e.1 as usize;
 
-        if self.preds[u] != all_ones() {
+        if self.preds[u] != all_ones::<N>() {
             self.preds[u] |= self.preds[v];
-            if self.preds[u] == all_ones() { // now u is reached by everybody
-                self.preds2[u] |= 1 << u; // it can thus be reached from "sink u"
+            if self.preds[u] == all_ones::<N>() { // now u is reached by everybody
+                self.preds2[u] |= VertexBits::<N>::bit(u); // it can thus be reached from "sink u"
             }
         }
-        if self.preds[v] != all_ones() {
+        if self.preds[v] != all_ones::<N>() {
             self.preds[v] |= self.preds[u];
-            if self.preds[v] == all_ones() {
-                self.preds2[v] |= 1 << v;
+            if self.preds[v] == all_ones::<N>() {
+                self.preds2[v] |= VertexBits::<N>::bit(v);
             }
         }
         self.preds2[u] |= self.preds2[v];
@@ -42,23 +42,30 @@ impl Reachability {
     }
 }
 
+impl<const N: usize> Default for Reachability<N> where Dim<N>: Width {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Incremental degree information used for testing dismountability.
 #[derive(Clone, Debug)]
-pub struct Dismountability {
+pub struct Dismountability<const N: usize> where Dim<N>: Width {
     pub(crate) degrees: [u8; N],
-    pub(crate) missing_ng:[u8; N],
-    pub(crate) mins: u8,
-    pub(crate) maxs: u8,
+    pub(crate) missing_ng:[VertexBits<N>; N],
+    pub(crate) mins: VertexBits<N>,
+    pub(crate) maxs: VertexBits<N>,
 }
 
-impl Dismountability {
-    pub fn new() -> Dismountability {
-        let mut missing_ng:[u8; N] = [all_ones(); N];
-        for u in 0..N{
-            missing_ng[u] ^= 1 << u;
+impl<const N: usize> Dismountability<N> where Dim<N>: Width {
+    pub fn new() -> Dismountability<N> {
+        let mut missing_ng = [all_ones::<N>(); N];
+        for (u, ng) in missing_ng.iter_mut().enumerate(){
+            *ng ^= VertexBits::<N>::bit(u);
         }
         Dismountability {
             degrees: [0_u8; N], missing_ng,
-            mins: 0, maxs: 0
+            mins: VertexBits::<N>::ZERO, maxs: VertexBits::<N>::ZERO
         }
     }
 
@@ -68,67 +75,92 @@ impl Dismountability {
 
         self.degrees[u] += 1;
         self.degrees[v] += 1;
-        self.missing_ng[u] ^= 1 << v;
-        self.missing_ng[v] ^= 1 << u;
+        self.missing_ng[u] ^= VertexBits::<N>::bit(v);
+        self.missing_ng[v] ^= VertexBits::<N>::bit(u);
 
         if self.degrees[u] == 1{
-            self.mins |= 1 << v;
+            self.mins |= VertexBits::<N>::bit(v);
         }
         if self.degrees[v] == 1{
-            self.mins |= 1 << u;
+            self.mins |= VertexBits::<N>::bit(u);
         }
         if self.degrees[u] == (N - 1) as u8 {
-            self.maxs |= 1 << v;
+            self.maxs |= VertexBits::<N>::bit(v);
         }
         if self.degrees[v] == (N - 1) as u8 {
-            self.maxs |= 1 << u;
+            self.maxs |= VertexBits::<N>::bit(u);
         }
     }
 }
 
+impl<const N: usize> Default for Dismountability<N> where Dim<N>: Width {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 
+/// A simple temporal graph on N vertices, as built by the generator: the edges added by the
+/// k-th call to [`TGraph::extends_by`] form a matching and have time k.
 #[derive(Clone, Debug)]
-pub struct TGraph {
-    pub(crate) n: u8,
+pub struct TGraph<const N: usize> where Dim<N>: Width {
     tmax: u8, // largest timestamp
-    pub(crate) times: [u8; M],
-    pub(crate) edges: [TEdge; M],
-    pub(crate) edges_bits: u32,
+    pub(crate) times: EdgeArray<u8, N>,
+    pub(crate) edges: EdgeArray<TEdge, N>,
+    pub(crate) edges_bits: EdgeBits<N>,
     pub(crate) nb_edges: u8,
-    pub(crate) cand_bits: u32,
+    pub(crate) cand_bits: EdgeBits<N>,
     pub(crate) nb_cand_edges: usize,
-    pub(crate) reachability: Reachability,
-    pub(crate) dismountability: Dismountability,
-    pub(crate) gens: Option<Vec<Vec<u8>>>,
+    pub(crate) reachability: Reachability<N>,
+    pub(crate) dismountability: Dismountability<N>,
+    pub(crate) gens: Option<Vec<Vec<u8>>>, // generators of the automorphism group (none for the empty graph, see below)
+    pub(crate) order: u64, // order of the automorphism group
 }
 
-impl TGraph {
-    pub fn new(n: u8) -> TGraph {
-        TGraph {n, tmax: 0, times: [0; M], edges: [TEdge(0,0,0); M], edges_bits: 0,
-            nb_edges: 0, cand_bits: !0, nb_cand_edges: M,
-            reachability: Reachability::new(n),
+impl<const N: usize> TGraph<N> where Dim<N>: Width {
+    pub const M: usize = nb_pairs(N); // number of vertex pairs
+    pub const DNMT: u8 = (2 * N).saturating_sub(3) as u8; // size of an optimal spanner
+
+    // A symmetric graph keeps a generating set of its automorphism group and its order, rather
+    // than all its automorphisms (a graph on 12 vertices with a single edge has 2·10! of them).
+    // The empty graph, whose automorphisms are all the N! permutations, keeps no generators:
+    // its successors are obtained directly (one matching per size).
+    /// The empty graph, root of the generation tree.
+    pub fn new() -> TGraph<N> {
+        TGraph {tmax: 0, times: EdgeArray::new(0), edges: EdgeArray::new(TEdge(0,0,0)),
+            edges_bits: EdgeBits::<N>::ZERO, nb_edges: 0,
+            cand_bits: !EdgeBits::<N>::ZERO, nb_cand_edges: Self::M,
+            reachability: Reachability::new(),
             dismountability: Dismountability::new(),
-            gens: Some((0..n).permutations(n as usize).collect())}
+            gens: Some(vec![]), order: (1..=N as u64).product()}
+    }
+    /// Number of vertices.
+    pub fn n(&self) -> u8{
+        N as u8
     }
+    /// Largest time of an edge (0 for the empty graph).
     pub fn tmax(&self) -> u8{
         self.tmax
     }
+    /// Whether the graph has a nontrivial automorphism (once rigid, all its descendants are).
     pub fn has_symmetries(&self) -> bool{
-        !self.gens.is_none()
+        self.gens.is_some()
     }
-    pub fn extends_by(&self, indicators: &u32, cache: &Cache) -> TGraph {
-        let mut times = self.times.clone();
-        let mut edges = self.edges.clone();
+    /// Child graph obtained by adding the edges of `indicators` (a matching, as a set of edge
+    /// indices, see [`Cache::edge`]) at time `tmax() + 1`.
+    pub fn extends_by(&self, indicators: &EdgeBits<N>, cache: &Cache<N>) -> TGraph<N> {
+        let mut times = self.times;
+        let mut edges = self.edges;
         let mut reachability = self.reachability.clone();
         let mut dismountability = self.dismountability.clone();
         let tmax = self.tmax() + 1;
         let mut edges_bits = self.edges_bits;
-        let mut cand_bits: u32 = 0;
+        let mut cand_bits = EdgeBits::<N>::ZERO;
         let mut nb_edges = self.nb_edges;
-        for i in 0..M{
-            if indicators & 1 << i != 0{
+        for i in 0..Self::M{
+            if indicators.has(i){
                 times[i] = tmax;
-                edges_bits |= 1 << i;
+                edges_bits |= EdgeBits::<N>::bit(i);
                 cand_bits |= cache.adjacent_bits[i];
                 let mut ne = cache.edges[i];
                 ne.2 = tmax;
@@ -140,50 +172,60 @@ impl TGraph {
         }
         cand_bits &= !edges_bits;
         let nb_cand_edges: usize = cand_bits.count_ones() as usize;
-        if ! self.gens.is_none() {
-            let gens:Vec<Vec<u8>> = self.gens.as_ref().unwrap().iter().filter(|a| {
-                is_automorphism(&times, a, cache)
-            }).map(|a| a.clone()).collect();
-            if gens.len() > 1{
-                return TGraph { n: self.n, tmax, times, edges, edges_bits,
+        if self.gens.is_some() {
+            let (gens, order) = automorphism_generators(&times, cache);
+            if order > 1{
+                return TGraph { tmax, times, edges, edges_bits,
                     nb_edges, cand_bits, nb_cand_edges,
                     reachability,
                     dismountability,
-                    gens: Some(gens)};
+                    gens: Some(gens), order};
             }
         }
-        TGraph {n: self.n, tmax, times, edges, edges_bits,
+        TGraph {tmax, times, edges, edges_bits,
             nb_edges, cand_bits, nb_cand_edges,
             reachability,
             dismountability,
-            gens: None}
+            gens: None, order: 1}
     }
 
-    pub fn get_matchings(&self, cache: &Cache) -> Vec<Vec<usize>> {
+    /// Candidate matchings for the next layer, up to automorphism (for symmetric graphs): the
+    /// first matching of each orbit.
+    pub fn get_matchings(&self, cache: &Cache<N>) -> Vec<Vec<usize>> {
         let mut matchings: Vec<Vec<usize>> = vec![];
-        for bits in get_indicators(self.nb_cand_edges, cache) {
-            if self.cand_bits | bits == self.cand_bits {
-                let matching = bits_to_indices(*bits);
-                if !contains_same_matching_up_to_automorphisms(self, &matchings, &matching, cache) {
-                    matchings.push(matching);
+        let mut seen: HashSet<EdgeBits<N>> = HashSet::new(); // the orbits of these matchings
+        for &bits in get_indicators(self.nb_cand_edges, cache) {
+            if self.cand_bits | bits != self.cand_bits || seen.contains(&bits) {
+                continue;
+            }
+            if self.nb_edges == 0 {
+                // any two matchings of the same size are equivalent in the empty graph
+                if matchings.last().is_some_and(|m| m.len() == bits.count_ones() as usize) {
+                    continue;
                 }
+            } else {
+                seen.extend(edge_orbit(bits, self.gens.as_deref().unwrap_or_default(), cache));
             }
+            matchings.push(bits_to_indices(bits));
         }
         matchings
     }
 
-    pub fn successors_rigid<'a>(&'a self, cache: &'a Cache) -> impl Iterator<Item=TGraph> + 'a {
-            get_indicators(self.nb_cand_edges, cache).into_iter()
+    /// Children of a rigid graph in the generation tree.
+    pub fn successors_rigid<'a>(&'a self, cache: &'a Cache<N>) -> impl Iterator<Item=TGraph<N>> + 'a {
+            get_indicators(self.nb_cand_edges, cache).iter()
                 .filter(|&&bits| { self.cand_bits | bits == self.cand_bits })
-                .map(|bits| { self.extends_by(&bits, cache) })
+                .map(|bits| { self.extends_by(bits, cache) })
     }
 
-    pub fn successors_aut<'a>(&'a self, cache: &'a Cache) -> impl Iterator<Item=TGraph> + 'a {
+    /// Children of a symmetric graph in the generation tree (one per orbit of matchings).
+    pub fn successors_aut<'a>(&'a self, cache: &'a Cache<N>) -> impl Iterator<Item=TGraph<N>> + 'a {
         self.get_matchings(cache).into_iter()
             .map(|m| self.extends_by(&indices_to_bits(&m), cache) )
     }
 
-    pub fn successors<'a>(&'a self, cache: &'a Cache) -> Either<impl Iterator<Item=TGraph> + 'a, impl Iterator<Item=TGraph> + 'a> {
+    /// Children of the graph in the generation tree.
+    pub fn successors<'a>(&'a self, cache: &'a Cache<N>) -> Either<impl Iterator<Item=TGraph<N>> + 'a, impl Iterator<Item=TGraph<N>> + 'a> {
         if self.has_symmetries() {
             Left(self.successors_aut(cache))
         } else {
@@ -191,11 +233,48 @@ impl TGraph {
         }
     }
 
+    /// Number of edges.
+    pub fn nb_edges(&self) -> usize {
+        self.nb_edges as usize
+    }
+    /// Time of the edge between u and v, if any (None if u = v or if they are not vertices).
+    pub fn time(&self, u: u8, v: u8) -> Option<u8> {
+        if u == v || u as usize >= N || v as usize >= N {
+            return None;
+        }
+        let t = self.times[e2i(N as u8, u, v)];
+        if t == 0 { None } else { Some(t) }
+    }
+    /// Set of edges (as bits indexed like [`Cache::edge`]).
+    pub fn edges_bits(&self) -> EdgeBits<N> {
+        self.edges_bits
+    }
+    /// Set of edges that can be added in the next layer (adjacent to an edge of the last layer).
+    pub fn cand_bits(&self) -> EdgeBits<N> {
+        self.cand_bits
+    }
+    /// Degree of every vertex.
+    pub fn degrees(&self) -> [u8; N] {
+        self.dismountability.degrees
+    }
+    /// Timed edges, in chronological order.
     pub fn tedges(&self) -> &[TEdge] {
-        return &self.edges[0..self.nb_edges as usize];
+        &self.edges.as_slice()[0..self.nb_edges as usize]
     }
 
-    pub fn predecessors(&self) -> [u8; N] {
+    /// The matchings added at times 1, ..., `tmax()`, as sets of edge indices.
+    pub fn layers(&self) -> Vec<EdgeBits<N>> {
+        let mut layers = vec![EdgeBits::<N>::ZERO; self.tmax as usize];
+        for (i, &t) in self.times.as_slice().iter().enumerate() {
+            if t > 0 {
+                layers[t as usize - 1] |= EdgeBits::<N>::bit(i);
+            }
+        }
+        layers
+    }
+
+    /// For each vertex, the set of vertices that can reach it (as bits).
+    pub fn predecessors(&self) -> [VertexBits<N>; N] {
         self.reachability.preds
         // let mut preds = [0_32; N];
         // for i in 0..self.n as usize {
@@ -210,39 +289,245 @@ impl TGraph {
     }
 }
 
-pub fn is_automorphism(times: &[u8; M], p: &[u8], cache: &Cache) -> bool {
-    for (i, t) in times.iter().enumerate().rev() {
+/// Error when building a graph from timed edges, see [`TGraph::from_tedges`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TEdgesError {
+    /// The number of vertices is larger than N.
+    TooManyVertices(usize),
+    /// The vertex is not smaller than the number of vertices.
+    VertexOutOfRange(u8),
+    /// The graph is not simple: there is a loop on a vertex, or several edges between two vertices.
+    NotSimple(u8, u8),
+    /// The graph is not proper: the vertex has several edges at the time.
+    NotProper(u8, u8),
+}
+
+impl fmt::Display for TEdgesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TEdgesError::TooManyVertices(n) => write!(f, "too many vertices ({})", n),
+            TEdgesError::VertexOutOfRange(u) => write!(f, "vertex {} out of range", u),
+            TEdgesError::NotSimple(u, v) => write!(f, "not simple: several edges between {} and {}", u, v),
+            TEdgesError::NotProper(u, t) => write!(f, "not proper: several edges of {} at time {}", u, t),
+        }
+    }
+}
+
+impl Error for TEdgesError {}
+
+impl<const N: usize> TGraph<N> where Dim<N>: Width {
+    /// Graph on n vertices (at most N, the others being isolated) with the given timed edges.
+    /// The edges are grouped by time into layers, the distinct times becoming 1, 2, ...
+    /// (which preserves the temporal paths). The graph must be simple and proper.
+    pub fn from_tedges(n: usize, tedges: &[TEdge], cache: &Cache<N>) -> Result<TGraph<N>, TEdgesError> {
+        if n > N {
+            return Err(TEdgesError::TooManyVertices(n));
+        }
+        let mut sorted = tedges.to_vec();
+        sorted.sort_by_key(|e| e.2);
+        let mut g = TGraph::new();
+        let mut edges_bits = EdgeBits::<N>::ZERO;
+        let mut layer = EdgeBits::<N>::ZERO;
+        let mut time = None;
+        for e in sorted {
+            if let Some(&u) = [e.0, e.1].iter().find(|&&u| u as usize >= n) {
+                return Err(TEdgesError::VertexOutOfRange(u));
+            }
+            let i = cache.edge_index(e.0, e.1);
+            if e.0 == e.1 || edges_bits.has(i) {
+                return Err(TEdgesError::NotSimple(e.0.min(e.1), e.0.max(e.1)));
+            }
+            if time != Some(e.2) {
+                if layer != EdgeBits::<N>::ZERO {
+                    g = g.extends_by(&layer, cache);
+                }
+                layer = EdgeBits::<N>::ZERO;
+                time = Some(e.2);
+            }
+            let conflicts = layer & cache.adjacent_bits[i];
+            if conflicts != EdgeBits::<N>::ZERO {
+                let other = cache.edges[conflicts.trailing_zeros() as usize];
+                let shared = if [other.0, other.1].contains(&e.0) { e.0 } else { e.1 };
+                return Err(TEdgesError::NotProper(shared, e.2));
+            }
+            layer |= EdgeBits::<N>::bit(i);
+            edges_bits |= EdgeBits::<N>::bit(i);
+        }
+        if layer != EdgeBits::<N>::ZERO {
+            g = g.extends_by(&layer, cache);
+        }
+        Ok(g)
+    }
+
+    /// The graph with the same temporal paths and the smallest times, as generated by STGen:
+    /// the time of an edge becomes one plus the largest new time of the adjacent edges that
+    /// are earlier (1 if there is none).
+    pub fn representative(&self, cache: &Cache<N>) -> TGraph<N> {
+        let mut tedges = self.tedges().to_vec();
+        let mut new_times = EdgeArray::<u8, N>::new(0);
+        for e in tedges.iter_mut() {
+            let i = cache.edge_index(e.0, e.1);
+            let earlier = cache.adjacent_bits[i] & self.edges_bits;
+            let t = (0..Self::M).filter(|&j| earlier.has(j) && self.times[j] < e.2)
+                .map(|j| new_times[j])
+                .max().unwrap_or(0) + 1;
+            new_times[i] = t;
+            e.2 = t;
+        }
+        TGraph::from_tedges(N, &tedges, cache).unwrap()
+    }
+}
+
+impl<const N: usize> Default for TGraph<N> where Dim<N>: Width {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn is_automorphism<const N: usize>(times: &EdgeArray<u8, N>, p: &[u8], cache: &Cache<N>) -> bool where Dim<N>: Width {
+    is_isomorphism(times, times, p, cache)
+}
+
+// Whether p maps every pair of vertices to a pair with the same time
+pub(crate) fn is_isomorphism<const N: usize>(times: &EdgeArray<u8, N>, other_times: &EdgeArray<u8, N>, p: &[u8], cache: &Cache<N>) -> bool where Dim<N>: Width {
+    for (i, t) in times.as_slice().iter().enumerate().rev() {
         let e = cache.edges[i];
         let i = cache.e2i[p[e.0 as usize] as usize][p[e.1 as usize] as usize];
-        if times[i] != *t {
+        if other_times[i] != *t {
             return false;
         }
     }
     true
 }
 
-pub fn same_matching_by_perm(vec1 : &[usize], vec2 : &[usize], perm : &[u8], cache: &Cache) -> bool {
-    for i in vec1.iter(){
-        let u2: usize = perm[cache.edges[*i].0 as usize] as usize;
-        let v2: usize = perm[cache.edges[*i].1 as usize] as usize;
-        let i2 = cache.e2i[u2][v2];
-        if !vec2.contains(&i2){
-            return false;
+// Image of a set of edges by the permutation p of the vertices
+pub(crate) fn permute_edges<const N: usize>(bits: EdgeBits<N>, p: &[u8], cache: &Cache<N>) -> EdgeBits<N> where Dim<N>: Width {
+    let mut image = EdgeBits::<N>::ZERO;
+    for i in bits_to_indices(bits) {
+        let e = cache.edges[i];
+        image |= EdgeBits::<N>::bit(cache.e2i[p[e.0 as usize] as usize][p[e.1 as usize] as usize]);
+    }
+    image
+}
+
+// Orbit of a set of edges under the group generated by gens
+pub(crate) fn edge_orbit<const N: usize>(bits: EdgeBits<N>, gens: &[Vec<u8>], cache: &Cache<N>) -> HashSet<EdgeBits<N>> where Dim<N>: Width {
+    let mut orbit = HashSet::from([bits]);
+    let mut stack = vec![bits];
+    while let Some(bits) = stack.pop() {
+        for p in gens {
+            let image = permute_edges(bits, p, cache);
+            if orbit.insert(image) {
+                stack.push(image);
+            }
         }
     }
-    true
+    orbit
 }
 
-pub fn contains_same_matching_up_to_automorphisms(g: &TGraph, matchings: &Vec<Vec<usize>>, m: &Vec<usize>, cache: &Cache) -> bool {
-    for m2 in matchings.iter().rev() {
-        if m.len() == m2.len() {
-            for perm in g.gens.as_ref().unwrap().iter() {
-                if same_matching_by_perm(m, m2, perm, cache) {
+// An automorphism that maps the vertices u < prefix.len() to prefix[u], if any (by backtracking
+// on the images of the other vertices)
+fn find_automorphism<const N: usize>(prefix: &[u8], times: &EdgeArray<u8, N>, cache: &Cache<N>) -> Option<Vec<u8>> where Dim<N>: Width {
+    fn extend<const N: usize>(perm: &mut Vec<u8>, used: &mut [bool; N], prefix: &[u8], times: &EdgeArray<u8, N>, cache: &Cache<N>) -> bool where Dim<N>: Width {
+        let u = perm.len();
+        if u == N {
+            return true;
+        }
+        let images = if u < prefix.len() { prefix[u] as usize..prefix[u] as usize + 1 } else { 0..N };
+        for x in images {
+            if !used[x] && (0..u).all(|v| times[cache.e2i[u][v]] == times[cache.e2i[x][perm[v] as usize]]) {
+                used[x] = true;
+                perm.push(x as u8);
+                if extend(perm, used, prefix, times, cache) {
                     return true;
                 }
+                perm.pop();
+                used[x] = false;
             }
         }
+        false
     }
-    return false;
+    let mut perm = Vec::with_capacity(N);
+    extend(&mut perm, &mut [false; N], prefix, times, cache).then_some(perm)
 }
 
+// A generating set of the permutations of the vertices that preserve the time of every pair,
+// and their number. For u = N-1, ..., 0, the automorphisms fixing the vertices before u are
+// generated by those found so far and by one automorphism mapping u to each vertex out of the
+// orbit of u (so that each generator is not generated by the previous ones), and their number is
+// the size of this orbit times the number of automorphisms fixing u as well.
+pub(crate) fn automorphism_generators<const N: usize>(times: &EdgeArray<u8, N>, cache: &Cache<N>) -> (Vec<Vec<u8>>, u64) where Dim<N>: Width {
+    let mut gens: Vec<Vec<u8>> = vec![];
+    let mut order = 1;
+    for u in (0..N).rev() {
+        let mut orbit = vertex_orbit(u as u8, &gens);
+        for x in u + 1..N {
+            if !orbit.contains(&(x as u8)) {
+                let prefix: Vec<u8> = (0..u as u8).chain([x as u8]).collect();
+                if let Some(p) = find_automorphism(&prefix, times, cache) {
+                    gens.push(p);
+                    orbit = vertex_orbit(u as u8, &gens);
+                }
+            }
+        }
+        order *= orbit.len() as u64;
+    }
+    (gens, order)
+}
+
+// Orbit of the vertex v under the group generated by gens
+pub(crate) fn vertex_orbit(v: u8, gens: &[Vec<u8>]) -> Vec<u8> {
+    let mut orbit = vec![v];
+    let mut i = 0;
+    while i < orbit.len() {
+        for p in gens {
+            let image = p[orbit[i] as usize];
+            if !orbit.contains(&image) {
+                orbit.push(image);
+            }
+        }
+        i += 1;
+    }
+    orbit
+}
+
+#[test]
+pub fn build_from_tedges() {
+    let cache = crate::cache::compute_cache::<5>();
+    for g in crate::descendants(TGraph::<5>::new(), None, &cache).step_by(7) {
+        let mut tedges = g.tedges().to_vec();
+        tedges.reverse();
+        let h = TGraph::<5>::from_tedges(5, &tedges, &cache).unwrap();
+        assert_eq!(g.tedges(), h.tedges());
+        assert_eq!(g.has_symmetries(), h.has_symmetries());
+        assert_eq!(g.is_dismountable(), h.is_dismountable());
+    }
+    // 1-2 and 2-3 both at time 7
+    let g = TGraph::<5>::from_tedges(4, &[TEdge(2, 3, 7), TEdge(0, 1, 3), TEdge(1, 2, 7)], &cache);
+    assert_eq!(g.map(|g| g.tedges().to_vec()), Err(TEdgesError::NotProper(2, 7)));
+    let g = TGraph::<5>::from_tedges(4, &[TEdge(2, 3, 7), TEdge(0, 1, 3), TEdge(1, 0, 7)], &cache);
+    assert_eq!(g.map(|g| g.tedges().to_vec()), Err(TEdgesError::NotSimple(0, 1)));
+    // times 3, 5 and 7 become 1, 2 and 3
+    let g = TGraph::<5>::from_tedges(4, &[TEdge(2, 3, 7), TEdge(0, 1, 3), TEdge(1, 2, 5)], &cache).unwrap();
+    assert_eq!(g.tedges(), &[TEdge(0, 1, 1), TEdge(1, 2, 2), TEdge(2, 3, 3)]);
+    assert_eq!((g.time(2, 1), g.time(0, 3), g.time(1, 1), g.time(0, 5)), (Some(2), None, None, None));
+    assert!(TGraph::<5>::from_tedges(4, &[TEdge(0, 4, 1)], &cache).is_err_and(|e| e == TEdgesError::VertexOutOfRange(4)));
+    assert!(TGraph::<5>::from_tedges(4, &[TEdge(1, 1, 1)], &cache).is_err_and(|e| e == TEdgesError::NotSimple(1, 1)));
+    assert!(TGraph::<5>::from_tedges(6, &[], &cache).is_err_and(|e| e == TEdgesError::TooManyVertices(6)));
+}
+
+#[test]
+pub fn time_compression() {
+    let cache = crate::cache::compute_cache::<4>();
+    // G1 and G2 of the README (a, b, c, d = top left, top right, bottom left, bottom right)
+    let g1 = TGraph::<4>::from_tedges(4, &[TEdge(0, 1, 1), TEdge(0, 2, 2), TEdge(1, 3, 4), TEdge(2, 3, 5)], &cache).unwrap();
+    let g2 = g1.representative(&cache);
+    assert_eq!(g2.tedges(), &[TEdge(0, 1, 1), TEdge(0, 2, 2), TEdge(1, 3, 2), TEdge(2, 3, 3)]);
+    for g in crate::descendants(TGraph::<4>::new(), None, &cache) {
+        assert_eq!(g.representative(&cache).tedges(), g.tedges());
+    }
+    // independent edges share the first time
+    let g = TGraph::<4>::from_tedges(4, &[TEdge(0, 1, 1), TEdge(2, 3, 2), TEdge(1, 2, 3)], &cache).unwrap();
+    assert_eq!(g.representative(&cache).tedges(), &[TEdge(0, 1, 1), TEdge(2, 3, 1), TEdge(1, 2, 2)]);
+}
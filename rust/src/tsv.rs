@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use crate::bits::{Dim, Width};
+use crate::cache::Cache;
+use crate::tedges::TEdge;
+use crate::tgraph::{TEdgesError, TGraph};
+
+// Import of temporal graphs given as link streams: one edge "u v t" per line, separated by
+// tabs or spaces, where u and v are vertex identifiers and t is an integer timestamp. Empty
+// lines and lines starting with '#' are ignored. The vertices are relabelled 0, 1, ... by
+// increasing identifier and the timestamps 1, 2, ... by increasing value, which preserves
+// the temporal paths.
+
+/// A temporal graph imported from a link stream, with its original vertices and timestamps.
+#[derive(Clone, Debug)]
+pub struct LinkStream<const N: usize> where Dim<N>: Width {
+    pub graph: TGraph<N>,
+    /// Identifier of each vertex of the graph in the file (vertices beyond are isolated).
+    pub vertices: Vec<u64>,
+    /// Timestamp in the file of each time 1, 2, ... of the graph.
+    pub timestamps: Vec<i64>,
+}
+
+/// Error when importing a link stream.
+#[derive(Debug)]
+pub enum TsvError {
+    Io(io::Error),
+    /// The line (numbered from 1) is not of the form "u v t".
+    Syntax(usize),
+    /// An edge has the same two ends (at the given line).
+    Loop(usize),
+    /// There are more vertices than N.
+    TooManyVertices(usize),
+    /// The graph is not simple: the two vertices have several edges.
+    NotSimple(u64, u64),
+    /// The graph is not proper: the vertex has several edges at the timestamp.
+    NotProper(u64, i64),
+}
+
+impl fmt::Display for TsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TsvError::Io(e) => write!(f, "{}", e),
+            TsvError::Syntax(line) => write!(f, "line {}: expected \"u v t\"", line),
+            TsvError::Loop(line) => write!(f, "line {}: an edge must have two distinct ends", line),
+            TsvError::TooManyVertices(nb) => write!(f, "{} vertices, more than n", nb),
+            TsvError::NotSimple(u, v) => write!(f, "not simple: several edges between {} and {}", u, v),
+            TsvError::NotProper(u, t) => write!(f, "not proper: several edges of {} at time {}", u, t),
+        }
+    }
+}
+
+impl Error for TsvError {}
+
+impl From<io::Error> for TsvError {
+    fn from(e: io::Error) -> TsvError {
+        TsvError::Io(e)
+    }
+}
+
+fn parse_line(line: &str) -> Option<(u64, u64, i64)> {
+    let mut words = line.split_whitespace();
+    let edge = (words.next()?.parse().ok()?, words.next()?.parse().ok()?, words.next()?.parse().ok()?);
+    words.next().is_none().then_some(edge)
+}
+
+fn read_links(input: impl BufRead) -> Result<Vec<(u64, u64, i64)>, TsvError> {
+    let mut links = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (u, v, t) = parse_line(line).ok_or(TsvError::Syntax(i + 1))?;
+        if u == v {
+            return Err(TsvError::Loop(i + 1));
+        }
+        links.push((u, v, t));
+    }
+    Ok(links)
+}
+
+fn sorted_vertices(links: &[(u64, u64, i64)]) -> Vec<u64> {
+    links.iter().flat_map(|&(u, v, _)| [u, v]).collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+/// Number of vertices of a link stream (see above), e.g. to choose N for [`read_tsv_file`].
+pub fn nb_vertices_tsv_file(path: impl AsRef<Path>) -> Result<usize, TsvError> {
+    Ok(sorted_vertices(&read_links(BufReader::new(File::open(path)?))?).len())
+}
+
+/// Reads a link stream (see above), which must be a simple and proper temporal graph on at
+/// most N vertices (the other vertices are isolated).
+pub fn read_tsv<const N: usize>(input: impl BufRead, cache: &Cache<N>) -> Result<LinkStream<N>, TsvError> where Dim<N>: Width {
+    let links = read_links(input)?;
+    let vertices = sorted_vertices(&links);
+    let timestamps: Vec<i64> = links.iter().map(|&(_, _, t)| t).collect::<BTreeSet<_>>().into_iter().collect();
+    if vertices.len() > N {
+        return Err(TsvError::TooManyVertices(vertices.len()));
+    }
+    if links.len() > TGraph::<N>::M { // then two links join the same vertices (and times may not fit in u8)
+        let mut pairs = BTreeSet::new();
+        let &(u, v, _) = links.iter().find(|&&(u, v, _)| !pairs.insert((u.min(v), u.max(v)))).unwrap();
+        return Err(TsvError::NotSimple(u.min(v), u.max(v)));
+    }
+    let tedges: Vec<TEdge> = links.iter()
+        .map(|(u, v, t)| TEdge(vertices.binary_search(u).unwrap() as u8, vertices.binary_search(v).unwrap() as u8,
+                               timestamps.binary_search(t).unwrap() as u8 + 1))
+        .collect();
+    let graph = TGraph::from_tedges(N, &tedges, cache).map_err(|e| match e {
+        TEdgesError::NotSimple(u, v) => TsvError::NotSimple(vertices[u as usize], vertices[v as usize]),
+        TEdgesError::NotProper(u, t) => TsvError::NotProper(vertices[u as usize], timestamps[t as usize - 1]),
+        TEdgesError::TooManyVertices(_) | TEdgesError::VertexOutOfRange(_) => unreachable!(),
+    })?;
+    Ok(LinkStream { graph, vertices, timestamps })
+}
+
+/// Reads a link stream from a file, see [`read_tsv`].
+pub fn read_tsv_file<const N: usize>(path: impl AsRef<Path>, cache: &Cache<N>) -> Result<LinkStream<N>, TsvError> where Dim<N>: Width {
+    read_tsv(BufReader::new(File::open(path)?), cache)
+}
+
+#[test]
+pub fn import_link_streams() {
+    let cache = crate::cache::compute_cache::<4>();
+    // a temporally connected square with a diagonal, with arbitrary labels and timestamps
+    let stream = read_tsv("# u v t\n10\t20\t-5\n20\t40\t7\n\n40 30 0\n30 10 100\n10 40 8\n".as_bytes(), &cache).unwrap();
+    assert_eq!(stream.vertices, vec![10, 20, 30, 40]);
+    assert_eq!(stream.timestamps, vec![-5, 0, 7, 8, 100]);
+    assert_eq!(stream.graph.tmax(), 5);
+    assert_eq!(stream.graph.nb_edges(), 5);
+    assert_eq!(stream.graph.time(0, 1), Some(1));
+    assert_eq!(stream.graph.time(2, 3), Some(2));
+    assert!(stream.graph.is_tc());
+    assert!(matches!(read_tsv("0 1 1\n1 0 2\n".as_bytes(), &cache), Err(TsvError::NotSimple(0, 1))));
+    assert!(matches!(read_tsv("0 1 1\n1 0 2\n0 1 3\n1 0 4\n0 1 5\n1 0 6\n0 1 7\n".as_bytes(), &cache), Err(TsvError::NotSimple(0, 1))));
+    assert!(matches!(read_tsv("0 1 1\n1 2 1\n".as_bytes(), &cache), Err(TsvError::NotProper(1, 1))));
+    assert!(matches!(read_tsv("0 1 1\n2 3\n".as_bytes(), &cache), Err(TsvError::Syntax(2))));
+    assert!(matches!(read_tsv("0 0 1\n".as_bytes(), &cache), Err(TsvError::Loop(1))));
+    assert!(matches!(read_tsv("0 1 1\n2 3 1\n4 5 2\n".as_bytes(), &cache), Err(TsvError::TooManyVertices(6))));
+    assert_eq!(sorted_vertices(&read_links("# triangle\n7 8 1\n8 9 2\n9 7 3\n".as_bytes()).unwrap()), vec![7, 8, 9]);
+}
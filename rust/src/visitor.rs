@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::algos::select;
+use crate::bits::{Dim, Width};
+use crate::cache::Cache;
+use crate::generation::{Keep, Select};
+use crate::tgraph::TGraph;
+
+// Visitors are the Rust counterpart of the callbacks of the C++ version (process_as_tgraph in
+// traverse.hpp): they are called on every graph of the generation tree and decide whether its
+// subtree is explored. Unlike the functions given to `descendants`, they can hold a state.
+// For the parallel traversals (see `parallel::visit_par`), every thread works with its own
+// copy of the visitor, obtained by `fork`, and the copies are merged at the end.
+
+/// What to do after visiting a graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    /// Explore the subtree of the graph.
+    Descend,
+    /// Skip the subtree of the graph.
+    Skip,
+    /// Stop the traversal.
+    Stop,
+}
+
+/// Callback called on the graphs of the generation tree, see [`visit`].
+pub trait Visitor<const N: usize>: Sized where Dim<N>: Width {
+    /// Processes `g`, deciding whether its subtree is explored.
+    fn visit(&mut self, g: &TGraph<N>) -> Control;
+    /// A visitor in the initial state, to visit other subtrees (in another thread).
+    fn fork(&self) -> Self;
+    /// Adds the results of `other`, which was obtained by [`Visitor::fork`].
+    fn merge(&mut self, other: Self);
+}
+
+/// Visits `g` and its descendants in the order of [`crate::descendants`], except for the
+/// subtrees skipped by the visitor. Returns false if the visitor stopped the traversal.
+pub fn visit<const N: usize>(g: TGraph<N>, visitor: &mut impl Visitor<N>, cache: &Cache<N>) -> bool where Dim<N>: Width {
+    visit_until(g, visitor, cache, &AtomicBool::new(false))
+}
+
+// Same as `visit`, also stopping when `stop` is set (by another thread), and setting it when
+// the visitor stops the traversal.
+pub(crate) fn visit_until<const N: usize>(g: TGraph<N>, visitor: &mut impl Visitor<N>, cache: &Cache<N>, stop: &AtomicBool) -> bool where Dim<N>: Width {
+    let mut stack = vec![g];
+    while let Some(g) = stack.pop() {
+        if stop.load(Ordering::Relaxed) {
+            return false;
+        }
+        match visitor.visit(&g) {
+            Control::Descend => stack.extend(g.successors(cache)),
+            Control::Skip => {}
+            Control::Stop => {
+                stop.store(true, Ordering::Relaxed);
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Counts the graphs satisfying `keep`.
+#[derive(Clone, Copy)]
+pub struct Counter<const N: usize> where Dim<N>: Width {
+    keep: Keep<N>,
+    pub nb: u64,
+}
+
+impl<const N: usize> Counter<N> where Dim<N>: Width {
+    pub fn new(keep: Keep<N>) -> Counter<N> {
+        Counter { keep, nb: 0 }
+    }
+}
+
+impl<const N: usize> Visitor<N> for Counter<N> where Dim<N>: Width {
+    fn visit(&mut self, g: &TGraph<N>) -> Control {
+        if (self.keep)(g) {
+            self.nb += 1;
+        }
+        Control::Descend
+    }
+
+    fn fork(&self) -> Self {
+        Counter::new(self.keep)
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.nb += other.nb;
+    }
+}
+
+/// Collects the graphs satisfying `keep`.
+#[derive(Clone)]
+pub struct Collector<const N: usize> where Dim<N>: Width {
+    keep: Keep<N>,
+    pub graphs: Vec<TGraph<N>>,
+}
+
+impl<const N: usize> Collector<N> where Dim<N>: Width {
+    pub fn new(keep: Keep<N>) -> Collector<N> {
+        Collector { keep, graphs: vec![] }
+    }
+}
+
+impl<const N: usize> Visitor<N> for Collector<N> where Dim<N>: Width {
+    fn visit(&mut self, g: &TGraph<N>) -> Control {
+        if (self.keep)(g) {
+            self.graphs.push(g.clone());
+        }
+        Control::Descend
+    }
+
+    fn fork(&self) -> Self {
+        Collector::new(self.keep)
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.graphs.extend(other.graphs);
+    }
+}
+
+/// Counts the graphs satisfying `keep` by order of their automorphism group, which also gives
+/// the number of labelled graphs they represent (see [`TGraph::automorphism_group`]).
+#[derive(Clone)]
+pub struct AutCounter<const N: usize> where Dim<N>: Width {
+    keep: Keep<N>,
+    /// Number of graphs for each order of the automorphism group.
+    pub by_order: BTreeMap<u64, u128>,
+}
+
+impl<const N: usize> AutCounter<N> where Dim<N>: Width {
+    pub fn new(keep: Keep<N>) -> AutCounter<N> {
+        AutCounter { keep, by_order: BTreeMap::new() }
+    }
+
+    /// Number of graphs counted.
+    pub fn nb(&self) -> u128 {
+        self.by_order.values().sum()
+    }
+
+    /// Number of rigid graphs counted (whose automorphism group is trivial).
+    pub fn nb_rigid(&self) -> u128 {
+        self.by_order.get(&1).copied().unwrap_or(0)
+    }
+
+    /// Number of labelled graphs on N vertices isomorphic to the graphs counted, i.e. the sum of
+    /// N!/|Aut(G)| over the graphs G counted.
+    pub fn nb_labelled(&self) -> u128 {
+        let factorial: u128 = (1..=N as u128).product();
+        self.by_order.iter().map(|(&order, &nb)| nb * (factorial / order as u128)).sum()
+    }
+}
+
+impl<const N: usize> Visitor<N> for AutCounter<N> where Dim<N>: Width {
+    fn visit(&mut self, g: &TGraph<N>) -> Control {
+        if (self.keep)(g) {
+            *self.by_order.entry(g.automorphism_group().order()).or_default() += 1;
+        }
+        Control::Descend
+    }
+
+    fn fork(&self) -> Self {
+        AutCounter::new(self.keep)
+    }
+
+    fn merge(&mut self, other: Self) {
+        for (order, nb) in other.by_order {
+            *self.by_order.entry(order).or_default() += nb;
+        }
+    }
+}
+
+/// Restricts a visitor to the graphs for which `select` returns true: the other graphs are
+/// skipped together with their subtree, as with [`crate::descendants`].
+#[derive(Clone)]
+pub struct Selecting<'a, const N: usize, V> where Dim<N>: Width {
+    select: Option<Select<N>>,
+    cache: &'a Cache<N>,
+    pub inner: V,
+}
+
+impl<'a, const N: usize, V: Visitor<N>> Selecting<'a, N, V> where Dim<N>: Width {
+    pub fn new(select: Option<Select<N>>, cache: &'a Cache<N>, inner: V) -> Selecting<'a, N, V> {
+        Selecting { select, cache, inner }
+    }
+}
+
+impl<const N: usize, V: Visitor<N>> Visitor<N> for Selecting<'_, N, V> where Dim<N>: Width {
+    fn visit(&mut self, g: &TGraph<N>) -> Control {
+        match self.select {
+            Some(select) if !select(g, self.cache) => Control::Skip,
+            _ => self.inner.visit(g),
+        }
+    }
+
+    fn fork(&self) -> Self {
+        Selecting::new(self.select, self.cache, self.inner.fork())
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.inner.merge(other.inner);
+    }
+}
+
+/// Checks that the temporal cliques have a spanner of size at most 2n-3, skipping the subtrees
+/// that are known to satisfy it (see [`select`]). Stops on the first clique for which no such
+/// spanner was found.
+#[derive(Clone)]
+pub struct SpannerCheck<'a, const N: usize> where Dim<N>: Width {
+    cache: &'a Cache<N>,
+    /// Number of cliques checked.
+    pub nb: u64,
+    pub failure: Option<TGraph<N>>,
+}
+
+impl<'a, const N: usize> SpannerCheck<'a, N> where Dim<N>: Width {
+    pub fn new(cache: &'a Cache<N>) -> SpannerCheck<'a, N> {
+        SpannerCheck { cache, nb: 0, failure: None }
+    }
+}
+
+impl<const N: usize> Visitor<N> for SpannerCheck<'_, N> where Dim<N>: Width {
+    fn visit(&mut self, g: &TGraph<N>) -> Control {
+        if !select(g, self.cache) {
+            return Control::Skip;
+        }
+        if g.is_clique() {
+            self.nb += 1;
+            if !g.has_optimal_spanner(1000) {
+                self.failure = Some(g.clone());
+                return Control::Stop;
+            }
+        }
+        Control::Descend
+    }
+
+    fn fork(&self) -> Self {
+        SpannerCheck::new(self.cache)
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.nb += other.nb;
+        self.failure = self.failure.take().or(other.failure);
+    }
+}
+
+#[test]
+pub fn visitors() {
+    use crate::generation::{count, descendants};
+    let cache = crate::cache::compute_cache::<5>();
+    let mut counter = Counter::new(TGraph::is_tc);
+    assert!(visit(TGraph::<5>::new(), &mut counter, &cache));
+    assert_eq!(counter.nb, 10207);
+    let mut collector = Collector::new(|_| true);
+    visit(TGraph::<5>::new(), &mut collector, &cache);
+    let graphs: Vec<TGraph<5>> = descendants(TGraph::new(), None, &cache).collect();
+    assert!(collector.graphs.iter().map(TGraph::tedges).eq(graphs.iter().map(TGraph::tedges)));
+    let mut nondismountable = Selecting::new(Some(|g, _| !g.is_dismountable()), &cache, Counter::new(TGraph::is_clique));
+    visit(TGraph::<5>::new(), &mut nondismountable, &cache);
+    assert_eq!(nondismountable.inner.nb, count(TGraph::new(), Some(|g, _| !g.is_dismountable()), TGraph::is_clique, &cache));
+    // labelled STGs and cliques on 5 vertices (by brute force)
+    let mut orders = AutCounter::new(|_| true);
+    visit(TGraph::<5>::new(), &mut orders, &cache);
+    assert_eq!((orders.nb(), orders.nb_labelled()), (15378, 1844066));
+    let cache4 = crate::cache::compute_cache::<4>();
+    let mut orders = AutCounter::new(|_| true);
+    visit(TGraph::<4>::new(), &mut orders, &cache4);
+    // the symmetric STGs counted by count_symmetric in the Julia version (whose comment expects 14)
+    assert_eq!(orders.nb() - orders.nb_rigid(), 14);
+    let mut check = SpannerCheck::new(&cache);
+    assert!(visit(TGraph::<5>::new(), &mut check, &cache));
+    assert!(check.failure.is_none());
+    // stops on the first clique
+    struct FirstClique(Option<TGraph<5>>);
+    impl Visitor<5> for FirstClique {
+        fn visit(&mut self, g: &TGraph<5>) -> Control {
+            if g.is_clique() {
+                self.0 = Some(g.clone());
+                return Control::Stop;
+            }
+            Control::Descend
+        }
+        fn fork(&self) -> Self { FirstClique(None) }
+        fn merge(&mut self, other: Self) { self.0 = self.0.take().or(other.0); }
+    }
+    let mut first = FirstClique(None);
+    assert!(!visit(TGraph::<5>::new(), &mut first, &cache));
+    assert_eq!(first.0.unwrap().tedges(), graphs.iter().find(|g| g.is_clique()).unwrap().tedges());
+}
@@ -0,0 +1,83 @@
+use std::process::{Command, Output};
+
+fn stgen(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_stgen")).args(args).output().unwrap();
+    assert!(output.status.success(), "stgen {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+#[test]
+pub fn listed_graphs_alone_on_stdout() {
+    let output = stgen(&["list", "--n", "4", "--format", "jsonl"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut nb = 0;
+    for line in stdout.lines() {
+        let graph: serde_json::Value = serde_json::from_str(line).unwrap_or_else(|e| panic!("{:?}: {}", line, e));
+        assert_eq!(graph["n"], 4);
+        nb += 1;
+    }
+    assert_eq!(nb, 62);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Nombre pour n=4: 62"));
+    // the counts stay on the standard output
+    let stdout = String::from_utf8(stgen(&["count", "--n", "4"]).stdout).unwrap();
+    assert!(stdout.contains("Nombre pour n=4: 62"));
+}
+
+#[test]
+pub fn batch_results_alone_on_stdout() {
+    let stdout = String::from_utf8(stgen(&["batches", "--n", "5"]).stdout).unwrap();
+    let nb: usize = stdout.trim_end().parse().unwrap();
+    let mut count = 0;
+    for batch in 0..nb {
+        let stdout = stgen(&["run-batch", "--n", "5", "--batch", &batch.to_string()]).stdout;
+        let summary: serde_json::Value = serde_json::from_slice(&stdout).unwrap();
+        assert_eq!(summary["batch"], batch);
+        count += summary["count"].as_u64().unwrap();
+    }
+    assert_eq!(count, 15378);
+}
+
+#[test]
+pub fn invalid_options_rejected_before_counting() {
+    for args in [vec!["count", "--n", "8", "--memo", "--symmetric"], vec!["list", "--n", "8", "--memo", "--par"],
+                 vec!["count", "--n", "8", "--memo", "--par"], vec!["check-spanners", "--n", "8", "--memo", "--symmetric"],
+                 vec!["count", "--n", "8", "--memo-limit", "10"]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_stgen")).args(&args).output().unwrap();
+        assert_eq!(output.status.code(), Some(2), "stgen {:?}", args);
+        assert!(String::from_utf8(output.stderr).unwrap().contains("--memo"));
+    }
+    for args in [["check-spanners", "--n", "8", "--symmetric"], ["sample", "--n", "8", "--symmetric"]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_stgen")).args(args).output().unwrap();
+        assert_eq!(output.status.code(), Some(2), "stgen {:?}", args);
+        assert!(String::from_utf8(output.stderr).unwrap().contains("--symmetric"));
+    }
+}
+
+#[test]
+pub fn analyze_without_isolated_vertices() {
+    let path = std::env::temp_dir().join(format!("stgen-triangle-{}.tsv", std::process::id()));
+    std::fs::write(&path, "1 2 1\n2 3 2\n3 1 3\n").unwrap();
+    let stdout = String::from_utf8(stgen(&["analyze", path.to_str().unwrap()]).stdout).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(stdout.contains("n=3\n"));
+    assert!(stdout.contains("clique: true\n"));
+    assert!(stdout.contains("temporally connected: true\n"));
+    assert!(stdout.contains("automorphisms: 1 "));
+}
+
+#[test]
+pub fn edit_streams_only_for_listing() {
+    let path = std::env::temp_dir().join(format!("stgen-path-{}.tsv", std::process::id()));
+    std::fs::write(&path, "1 2 1\n2 3 2\n").unwrap();
+    let file = path.to_str().unwrap();
+    // the commands that write no graph ignore the format
+    for args in [vec!["analyze", file], vec!["locate", file], vec!["rank", file], vec!["batches", "--n", "4"], vec!["run-batch", "--n", "4", "--batch", "0"]] {
+        stgen(&[args, vec!["--format", "edits"]].concat());
+    }
+    std::fs::remove_file(&path).unwrap();
+    for args in [["unrank", "3"], ["sample", "--seed=1"]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_stgen")).args(args).args(["--n", "4", "--format", "edits"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8(output.stderr).unwrap().contains("--format edits"));
+    }
+}